@@ -0,0 +1,156 @@
+use crate::types::{Collector, CollectorStream};
+use alloy::primitives::{B256, U64};
+use alloy::providers::Provider;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Default number of recent blocks the base fee direction is computed over.
+const DEFAULT_WINDOW: usize = 5;
+/// Default weight of the newest base fee in the moving average.
+const DEFAULT_SMOOTHING: f64 = 0.3;
+
+/// Which way the base fee has moved over the recent blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BaseFeeDirection {
+    Rising,
+    Falling,
+    Flat,
+}
+
+/// A new block event, containing the block's base fee and its recent trend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseFeeBlock {
+    pub hash: B256,
+    pub number: U64,
+    /// Base fee of the block, in wei.
+    pub base_fee: u64,
+    /// Exponentially weighted moving average of the base fee, in wei.
+    pub ewma: f64,
+    /// Direction of the base fee over the recent blocks.
+    pub direction: BaseFeeDirection,
+}
+
+/// A collector that listens for new blocks, and generates a stream of
+/// [events](BaseFeeBlock) which contain the base fee along with its moving
+/// average and direction over the last few blocks. Blocks without a base fee
+/// are skipped.
+pub struct BaseFeeCollector<M> {
+    provider: Arc<M>,
+    window: usize,
+    smoothing: f64,
+}
+
+impl<M> BaseFeeCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            window: DEFAULT_WINDOW,
+            smoothing: DEFAULT_SMOOTHING,
+        }
+    }
+
+    /// Compute the direction over the last `window` blocks.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(2);
+        self
+    }
+
+    /// Weight the newest base fee by `smoothing` (between 0 and 1) in the
+    /// moving average.
+    pub fn with_smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(f64::EPSILON, 1.0);
+        self
+    }
+}
+
+/// Rolling state used to compute the base fee trend.
+struct BaseFeeTrend {
+    window: usize,
+    smoothing: f64,
+    recent: VecDeque<u64>,
+    ewma: Option<f64>,
+}
+
+impl BaseFeeTrend {
+    fn new(window: usize, smoothing: f64) -> Self {
+        Self {
+            window,
+            smoothing,
+            recent: VecDeque::with_capacity(window),
+            ewma: None,
+        }
+    }
+
+    /// Add the base fee of a new block, returning the updated moving average
+    /// and direction.
+    fn push(&mut self, base_fee: u64) -> (f64, BaseFeeDirection) {
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(base_fee);
+
+        let ewma = match self.ewma {
+            Some(ewma) => self.smoothing * base_fee as f64 + (1.0 - self.smoothing) * ewma,
+            None => base_fee as f64,
+        };
+        self.ewma = Some(ewma);
+
+        let oldest = self.recent.front().copied().unwrap_or(base_fee);
+        let direction = match base_fee.cmp(&oldest) {
+            std::cmp::Ordering::Greater => BaseFeeDirection::Rising,
+            std::cmp::Ordering::Less => BaseFeeDirection::Falling,
+            std::cmp::Ordering::Equal => BaseFeeDirection::Flat,
+        };
+        (ewma, direction)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [BaseFeeCollector](BaseFeeCollector).
+#[async_trait]
+impl<M> Collector<BaseFeeBlock> for BaseFeeCollector<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, BaseFeeBlock>> {
+        let mut trend = BaseFeeTrend::new(self.window, self.smoothing);
+        let stream = self
+            .provider
+            .subscribe_blocks()
+            .await?
+            .into_stream()
+            .filter_map(move |header| {
+                let event = header.base_fee_per_gas.map(|base_fee| {
+                    let (ewma, direction) = trend.push(base_fee);
+                    BaseFeeBlock {
+                        hash: header.hash,
+                        number: U64::from(header.number),
+                        base_fee,
+                        ewma,
+                        direction,
+                    }
+                });
+                futures::future::ready(event)
+            });
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_base_fee_trend() {
+        let mut trend = BaseFeeTrend::new(3, 0.5);
+        assert_eq!(trend.push(100), (100.0, BaseFeeDirection::Flat));
+        assert_eq!(trend.push(120), (110.0, BaseFeeDirection::Rising));
+        assert_eq!(trend.push(110), (110.0, BaseFeeDirection::Rising));
+        // 100 has left the window, so the trend is now measured from 120.
+        assert_eq!(trend.push(90), (100.0, BaseFeeDirection::Falling));
+    }
+}
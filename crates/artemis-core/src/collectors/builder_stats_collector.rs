@@ -0,0 +1,176 @@
+use crate::types::{Collector, CollectorStream, FLASHBOTS_BOOST_RELAY};
+use alloy::primitives::{B256, U256};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{trace, warn};
+
+/// Number of delivered payloads requested from the relay on each poll.
+const PAYLOADS_PER_POLL: usize = 100;
+
+/// A collector that periodically polls a MEV-Boost relay's data API for
+/// delivered payloads, and generates a stream of [events](BuilderBlockStats)
+/// describing which builder won each block.
+pub struct BuilderStatsCollector {
+    client: reqwest::Client,
+    relay_url: String,
+    interval: Duration,
+}
+
+/// A per-block summary of the winning builder's payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuilderBlockStats {
+    pub block_number: u64,
+    pub block_hash: B256,
+    /// BLS public key identifying the builder.
+    pub builder_pubkey: String,
+    pub gas_used: u64,
+    pub num_tx: u64,
+    /// Value paid to the proposer, in wei.
+    pub value: U256,
+}
+
+/// A delivered payload bid trace, as returned by the relay data API.
+#[derive(Debug, Deserialize)]
+struct BidTrace {
+    block_number: String,
+    block_hash: B256,
+    builder_pubkey: String,
+    gas_used: String,
+    num_tx: String,
+    value: String,
+}
+
+impl TryFrom<BidTrace> for BuilderBlockStats {
+    type Error = anyhow::Error;
+
+    fn try_from(trace: BidTrace) -> Result<Self> {
+        Ok(Self {
+            block_number: trace.block_number.parse()?,
+            block_hash: trace.block_hash,
+            builder_pubkey: trace.builder_pubkey,
+            gas_used: trace.gas_used.parse()?,
+            num_tx: trace.num_tx.parse()?,
+            value: trace.value.parse()?,
+        })
+    }
+}
+
+impl BuilderStatsCollector {
+    /// Create a collector polling the Flashbots relay every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self::with_relay(FLASHBOTS_BOOST_RELAY, interval)
+    }
+
+    /// Create a collector polling the given MEV-Boost relay every `interval`.
+    pub fn with_relay(relay_url: impl Into<String>, interval: Duration) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            relay_url: relay_url.into(),
+            interval,
+        }
+    }
+}
+
+/// Fetch the most recently delivered payloads from the relay, oldest first,
+/// skipping any with malformed traces.
+async fn fetch_delivered_payloads(
+    client: &reqwest::Client,
+    relay_url: &str,
+) -> Result<Vec<BuilderBlockStats>> {
+    let url = format!(
+        "{}/relay/v1/data/bidtraces/proposer_payload_delivered?limit={}",
+        relay_url.trim_end_matches('/'),
+        PAYLOADS_PER_POLL
+    );
+    let traces: Vec<BidTrace> = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    // skip malformed traces rather than dropping the rest of the poll
+    let mut stats: Vec<_> = traces
+        .into_iter()
+        .filter_map(|trace| {
+            BuilderBlockStats::try_from(trace)
+                .inspect_err(|err| warn!("skipping malformed bid trace: {err}"))
+                .ok()
+        })
+        .collect();
+    stats.sort_by_key(|stat| stat.block_number);
+    Ok(stats)
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [BuilderStatsCollector](BuilderStatsCollector).
+#[async_trait]
+impl Collector<BuilderBlockStats> for BuilderStatsCollector {
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, BuilderBlockStats>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let client = self.client.clone();
+        let relay_url = self.relay_url.clone();
+        let mut interval = tokio::time::interval(self.interval);
+
+        tokio::spawn(async move {
+            let mut last_block = 0;
+            loop {
+                interval.tick().await;
+                if tx.is_closed() {
+                    trace!("builder stats receiver dropped, stopping collector loop");
+                    break;
+                }
+                let stats = match fetch_delivered_payloads(&client, &relay_url).await {
+                    Ok(stats) => stats,
+                    Err(err) => {
+                        warn!("failed to fetch builder stats: {err}");
+                        continue;
+                    }
+                };
+
+                // only emit blocks we haven't seen on a previous poll
+                for stat in stats {
+                    if stat.block_number <= last_block {
+                        continue;
+                    }
+                    last_block = stat.block_number;
+                    if tx.send(stat).is_err() {
+                        trace!("builder stats receiver dropped, stopping collector loop");
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(UnboundedReceiverStream::new(rx)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_relay_bid_traces() {
+        let json = r#"[{
+            "slot": "7000000",
+            "block_number": "18000000",
+            "block_hash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "builder_pubkey": "0xa1b2",
+            "gas_limit": "30000000",
+            "gas_used": "12000000",
+            "num_tx": "150",
+            "value": "50000000000000000"
+        }]"#;
+        let traces: Vec<BidTrace> = serde_json::from_str(json).unwrap();
+        let stats = BuilderBlockStats::try_from(traces.into_iter().next().unwrap()).unwrap();
+        assert_eq!(stats.block_number, 18_000_000);
+        assert_eq!(stats.builder_pubkey, "0xa1b2");
+        assert_eq!(stats.num_tx, 150);
+        assert_eq!(stats.value, U256::from(50_000_000_000_000_000u64));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::types::{Collector, CollectorStream};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// A collector that wraps another [Collector](Collector) and coalesces events
+/// sharing a key. The first event for a key opens a window, and only the
+/// latest event for that key received within it is emitted when it closes,
+/// e.g. to collapse several MEV-Share events for the same opportunity into
+/// one. Events for different keys are emitted in the order their windows
+/// opened.
+pub struct CoalesceCollector<E, F> {
+    collector: Box<dyn Collector<E>>,
+    key: F,
+    window: Duration,
+}
+
+impl<E, F> CoalesceCollector<E, F> {
+    pub fn new(collector: Box<dyn Collector<E>>, key: F, window: Duration) -> Self {
+        Self {
+            collector,
+            key,
+            window,
+        }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [CoalesceCollector](CoalesceCollector).
+#[async_trait]
+impl<E, K, F> Collector<E> for CoalesceCollector<E, F>
+where
+    E: Send + 'static,
+    K: Eq + Hash + Clone + Send + 'static,
+    F: Fn(&E) -> K + Send + Sync + Clone + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, E>> {
+        let mut stream: CollectorStream<'static, E> = self.collector.get_event_stream().await?;
+        let key = self.key.clone();
+        let window = self.window;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut latest: HashMap<K, E> = HashMap::new();
+            // Keys with an open window, by when it closes. Every window is
+            // equally long, so they close in the order they opened.
+            let mut closing: VecDeque<(Instant, K)> = VecDeque::new();
+            loop {
+                let next_close = closing.front().map(|(at, _)| *at);
+                tokio::select! {
+                    event = stream.next() => match event {
+                        Some(event) => {
+                            let event_key = key(&event);
+                            if latest.insert(event_key.clone(), event).is_none() {
+                                closing.push_back((Instant::now() + window, event_key));
+                            }
+                        }
+                        None => {
+                            // flush the open windows before ending the stream
+                            for (_, event_key) in closing.drain(..) {
+                                if let Some(event) = latest.remove(&event_key) {
+                                    let _ = tx.send(event);
+                                }
+                            }
+                            break;
+                        }
+                    },
+                    _ = tokio::time::sleep_until(next_close.unwrap_or_else(Instant::now)),
+                        if next_close.is_some() =>
+                    {
+                        if let Some((_, event_key)) = closing.pop_front()
+                            && let Some(event) = latest.remove(&event_key)
+                            && tx.send(event).is_err()
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(Box::pin(UnboundedReceiverStream::new(rx)))
+    }
+
+    async fn close(&self) {
+        self.collector.close().await
+    }
+
+    fn name(&self) -> &str {
+        self.collector.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::ScriptedCollector;
+
+    #[tokio::test]
+    async fn emits_latest_event_per_key() {
+        let events = vec![("a", 1), ("b", 1), ("a", 2), ("a", 3)];
+        let collector = CoalesceCollector::new(
+            Box::new(ScriptedCollector::new(events)),
+            |event: &(&str, u64)| event.0,
+            Duration::from_secs(60),
+        );
+        let stream = collector.get_event_stream().await.unwrap();
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events, vec![("a", 3), ("b", 1)]);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::types::{Collector, CollectorStream};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::warn;
+
+/// A collector that replays events recorded in a JSON-lines file, and ends
+/// the stream at EOF. Each line is either a bare event, or a record of the form
+/// `{"timestamp_ms": <u64>, "event": <event>}` carrying the time it was seen.
+pub struct FileReplayCollector<E> {
+    path: PathBuf,
+    real_time: bool,
+    _event: PhantomData<fn() -> E>,
+}
+
+/// A single line of a replay file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReplayRecord<E> {
+    Timestamped { timestamp_ms: u64, event: E },
+    Bare(E),
+}
+
+impl<E> FileReplayCollector<E> {
+    /// Create a collector that replays the events in `path` as fast as possible.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            real_time: false,
+            _event: PhantomData,
+        }
+    }
+
+    /// Replay at real-time speed, waiting out the gap between recorded
+    /// timestamps before emitting each event.
+    pub fn real_time(mut self) -> Self {
+        self.real_time = true;
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [FileReplayCollector](FileReplayCollector).
+#[async_trait]
+impl<E> Collector<E> for FileReplayCollector<E>
+where
+    E: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, E>> {
+        let file = File::open(&self.path)
+            .await
+            .with_context(|| format!("failed to open replay file {}", self.path.display()))?;
+        let lines = BufReader::new(file).lines();
+        let real_time = self.real_time;
+
+        let stream = futures::stream::unfold(
+            (lines, None::<u64>),
+            move |(mut lines, mut last_timestamp)| async move {
+                loop {
+                    let line = match lines.next_line().await {
+                        Ok(Some(line)) => line,
+                        Ok(None) => return None,
+                        Err(err) => {
+                            warn!("failed to read replay file: {err}");
+                            return None;
+                        }
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    let (timestamp, event) = match serde_json::from_str(&line) {
+                        Ok(ReplayRecord::Timestamped {
+                            timestamp_ms,
+                            event,
+                        }) => (Some(timestamp_ms), event),
+                        Ok(ReplayRecord::Bare(event)) => (None, event),
+                        Err(err) => {
+                            warn!("skipping malformed replay line: {err}");
+                            continue;
+                        }
+                    };
+
+                    if let (true, Some(last), Some(timestamp)) =
+                        (real_time, last_timestamp, timestamp)
+                    {
+                        let delay = Duration::from_millis(timestamp.saturating_sub(last));
+                        tokio::time::sleep(delay).await;
+                    }
+                    last_timestamp = timestamp.or(last_timestamp);
+                    return Some((event, (lines, last_timestamp)));
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn replays_until_eof() {
+        let path = std::env::temp_dir().join(format!(
+            "artemis_file_replay_collector_{}.jsonl",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "1\n\n{\"timestamp_ms\": 10, \"event\": 2}\nnot json\n{\"timestamp_ms\": 15, \"event\": 3}\n",
+        )
+        .unwrap();
+
+        let collector = FileReplayCollector::<u64>::new(&path).real_time();
+        let events: Vec<u64> = collector.get_event_stream().await.unwrap().collect().await;
+        assert_eq!(events, vec![1, 2, 3]);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use crate::types::{Collector, CollectorStream};
+use alloy::consensus::Transaction as _;
+use alloy::primitives::{Address, B256};
+use alloy::providers::Provider;
+use alloy::rpc::types::eth::Transaction;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::trace;
+
+/// Default number of pending transactions fetched at once in hash mode.
+const DEFAULT_FETCH_CONCURRENCY: usize = 64;
+
+/// A collector that listens for pending transactions sent to any of a set of
+/// addresses, and generates a stream of [events](Transaction) which contain
+/// the transaction.
+///
+/// By default it subscribes to pending transaction hashes, and fetches each
+/// transaction to check its `to` address. The node only pushes hashes, which
+/// keeps the subscription cheap, but every pending transaction still costs a
+/// fetch, since a hash alone says nothing about its recipient. With
+/// [with_full_transactions](Self::with_full_transactions) it instead
+/// subscribes to full pending transactions and filters them locally: no extra
+/// requests, but the node pushes every transaction in full. Hash mode suits
+/// nodes on the same host, where fetches are cheap; full mode suits remote
+/// nodes, where round trips dominate.
+pub struct FilteredMempoolCollector<M> {
+    provider: Arc<M>,
+    targets: Arc<HashSet<Address>>,
+    full_transactions: bool,
+    fetch_concurrency: usize,
+}
+
+impl<M> FilteredMempoolCollector<M> {
+    pub fn new(provider: Arc<M>, targets: HashSet<Address>) -> Self {
+        Self {
+            provider,
+            targets: Arc::new(targets),
+            full_transactions: false,
+            fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+        }
+    }
+
+    /// Subscribe to full pending transactions instead of fetching each one
+    /// by hash.
+    pub fn with_full_transactions(mut self) -> Self {
+        self.full_transactions = true;
+        self
+    }
+
+    /// Fetch up to `concurrency` pending transactions at once in hash mode.
+    pub fn with_fetch_concurrency(mut self, concurrency: usize) -> Self {
+        self.fetch_concurrency = concurrency.max(1);
+        self
+    }
+}
+
+impl<M> FilteredMempoolCollector<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    /// Fetch the pending transactions of `hashes`, up to `concurrency` at
+    /// once, keeping those sent to one of `targets`.
+    fn fetch_targets(
+        provider: Arc<M>,
+        targets: Arc<HashSet<Address>>,
+        concurrency: usize,
+        hashes: impl Stream<Item = B256> + Send + 'static,
+    ) -> impl Stream<Item = Transaction> + Send + 'static {
+        hashes
+            .map(move |hash| {
+                let provider = provider.clone();
+                async move {
+                    match provider.get_transaction_by_hash(hash).await {
+                        Ok(tx) => tx,
+                        Err(e) => {
+                            trace!("failed to fetch pending transaction {hash}: {e}");
+                            None
+                        }
+                    }
+                }
+            })
+            .buffer_unordered(concurrency)
+            .filter_map(move |tx| futures::future::ready(tx.filter(|tx| is_sent_to(&targets, tx))))
+    }
+}
+
+/// Whether `tx` is sent to one of `targets`.
+fn is_sent_to(targets: &HashSet<Address>, tx: &Transaction) -> bool {
+    tx.to().is_some_and(|to| targets.contains(&to))
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [FilteredMempoolCollector](FilteredMempoolCollector).
+#[async_trait]
+impl<M> Collector<Transaction> for FilteredMempoolCollector<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, Transaction>> {
+        if self.full_transactions {
+            let targets = self.targets.clone();
+            let stream = self
+                .provider
+                .subscribe_full_pending_transactions()
+                .await?
+                .into_stream()
+                .filter(move |tx| futures::future::ready(is_sent_to(&targets, tx)));
+            return Ok(Box::pin(stream));
+        }
+
+        let hashes = self
+            .provider
+            .subscribe_pending_transactions()
+            .await?
+            .into_stream();
+        Ok(Box::pin(Self::fetch_targets(
+            self.provider.clone(),
+            self.targets.clone(),
+            self.fetch_concurrency,
+            hashes,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::MockProvider;
+    use alloy::consensus::transaction::Recovered;
+    use alloy::consensus::{SignableTransaction, TxEnvelope, TxLegacy};
+    use alloy::primitives::{Signature, TxKind, U256};
+
+    fn pending_tx(to: Address) -> Transaction {
+        let tx = TxLegacy {
+            to: TxKind::Call(to),
+            ..Default::default()
+        };
+        let signed = tx.into_signed(Signature::new(U256::from(1), U256::from(1), false));
+        Transaction {
+            inner: Recovered::new_unchecked(TxEnvelope::Legacy(signed), Address::ZERO),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            effective_gas_price: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_only_transactions_to_targets() {
+        let (target, other) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let provider = MockProvider::new();
+        provider.asserter().push_success(&pending_tx(other));
+        provider.asserter().push_success(&pending_tx(target));
+        // The transaction was dropped from the mempool before it was fetched.
+        provider.asserter().push_success(&None::<Transaction>);
+
+        let hashes = futures::stream::iter((1..=3).map(B256::with_last_byte));
+        let txs: Vec<_> = FilteredMempoolCollector::fetch_targets(
+            Arc::new(provider),
+            Arc::new(HashSet::from([target])),
+            1,
+            hashes,
+        )
+        .collect()
+        .await;
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].to(), Some(target));
+    }
+}
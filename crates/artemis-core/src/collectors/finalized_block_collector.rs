@@ -0,0 +1,176 @@
+use crate::collectors::block_collector::NewBlock;
+use crate::types::{Collector, CollectorStream};
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::U64;
+use alloy::providers::Provider;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{trace, warn};
+
+/// When a block is considered final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finality {
+    /// Once this many blocks have been built on top of it.
+    Confirmations(u64),
+    /// Once the node reports it under the `finalized` tag.
+    Finalized,
+}
+
+/// A collector that listens for new blocks, and generates a stream of
+/// [events](NewBlock) for blocks once they're final. Each final block is
+/// emitted exactly once, in order, starting from the first block that becomes
+/// final after the stream starts.
+pub struct FinalizedBlockCollector<M> {
+    provider: Arc<M>,
+    finality: Finality,
+}
+
+impl<M> FinalizedBlockCollector<M> {
+    pub fn new(provider: Arc<M>, finality: Finality) -> Self {
+        Self { provider, finality }
+    }
+}
+
+impl<M> FinalizedBlockCollector<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    /// The number of the latest final block, given the current head.
+    async fn final_block_number(
+        provider: &M,
+        finality: Finality,
+        head: u64,
+    ) -> Result<Option<u64>> {
+        match finality {
+            Finality::Confirmations(confirmations) => Ok(head.checked_sub(confirmations)),
+            Finality::Finalized => Ok(provider
+                .get_block_by_number(BlockNumberOrTag::Finalized)
+                .await?
+                .map(|block| block.header.number)),
+        }
+    }
+
+    /// Emit the blocks that become final as each of `heads` arrives.
+    fn final_blocks(
+        provider: Arc<M>,
+        finality: Finality,
+        mut heads: impl Stream<Item = u64> + Send + Unpin + 'static,
+    ) -> UnboundedReceiverStream<NewBlock> {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut last_emitted: Option<u64> = None;
+            while let Some(head) = heads.next().await {
+                let target = match Self::final_block_number(&provider, finality, head).await {
+                    Ok(Some(target)) => target,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warn!("failed to fetch final block number: {e}");
+                        continue;
+                    }
+                };
+
+                let first = last_emitted.map_or(target, |last| last + 1);
+                for number in first..=target {
+                    let block = match provider
+                        .get_block_by_number(BlockNumberOrTag::Number(number))
+                        .await
+                    {
+                        Ok(Some(block)) => block,
+                        Ok(None) => {
+                            warn!("final block {number} not found; retrying on next head");
+                            break;
+                        }
+                        Err(e) => {
+                            warn!(
+                                "failed to fetch final block {number}: {e}; retrying on next head"
+                            );
+                            break;
+                        }
+                    };
+                    let event = NewBlock {
+                        hash: block.header.hash,
+                        number: U64::from(number),
+                    };
+                    if tx.send(event).is_err() {
+                        trace!("finalized block receiver dropped, stopping collector");
+                        return;
+                    }
+                    last_emitted = Some(number);
+                }
+            }
+        });
+
+        UnboundedReceiverStream::new(rx)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [FinalizedBlockCollector](FinalizedBlockCollector). New heads are used to
+/// tell when to check for newly final blocks, which are then fetched by number.
+#[async_trait]
+impl<M> Collector<NewBlock> for FinalizedBlockCollector<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, NewBlock>> {
+        let heads = self
+            .provider
+            .subscribe_blocks()
+            .await?
+            .into_stream()
+            .map(|head| head.number);
+        Ok(Box::pin(Self::final_blocks(
+            self.provider.clone(),
+            self.finality,
+            heads,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::MockProvider;
+    use alloy::primitives::B256;
+    use alloy::rpc::types::eth::{Block, Header};
+
+    fn block(number: u64) -> Block {
+        Block::empty(Header {
+            hash: B256::with_last_byte(number as u8),
+            inner: alloy::consensus::Header {
+                number,
+                ..Default::default()
+            },
+            total_difficulty: None,
+            size: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn emits_each_confirmed_block_once_in_order() {
+        let provider = MockProvider::new();
+        for number in 8..=11 {
+            provider.asserter().push_success(&block(number));
+        }
+        // Head 12 is never seen, so blocks 10 and 11 become final at once.
+        let heads = futures::stream::iter([10, 11, 13]);
+        let blocks = FinalizedBlockCollector::final_blocks(
+            Arc::new(provider),
+            Finality::Confirmations(2),
+            heads,
+        );
+
+        let blocks: Vec<_> = blocks.collect().await;
+        let numbers: Vec<_> = blocks
+            .iter()
+            .map(|block| block.number.to::<u64>())
+            .collect();
+        assert_eq!(numbers, vec![8, 9, 10, 11]);
+        assert_eq!(blocks[3].hash, B256::with_last_byte(11));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::types::{Collector, CollectorStream};
+use alloy::primitives::U64;
+use alloy::providers::Provider;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A new block event, containing how much of the block's gas limit was used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockGasStats {
+    pub number: U64,
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    /// Share of the gas limit used, between 0 and 1.
+    pub utilization: f64,
+}
+
+impl BlockGasStats {
+    pub fn new(number: u64, gas_used: u64, gas_limit: u64) -> Self {
+        let utilization = if gas_limit == 0 {
+            0.0
+        } else {
+            gas_used as f64 / gas_limit as f64
+        };
+        Self {
+            number: U64::from(number),
+            gas_used,
+            gas_limit,
+            utilization,
+        }
+    }
+}
+
+/// A collector that listens for new blocks, and generates a stream of
+/// [events](BlockGasStats) which contain the block's gas usage, e.g. to model
+/// congestion.
+pub struct GasUsageCollector<M> {
+    provider: Arc<M>,
+}
+
+impl<M> GasUsageCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self { provider }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [GasUsageCollector](GasUsageCollector).
+#[async_trait]
+impl<M> Collector<BlockGasStats> for GasUsageCollector<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, BlockGasStats>> {
+        let stream = self
+            .provider
+            .subscribe_blocks()
+            .await?
+            .into_stream()
+            .map(|header| BlockGasStats::new(header.number, header.gas_used, header.gas_limit));
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_utilization() {
+        assert_eq!(
+            BlockGasStats::new(1, 15_000_000, 30_000_000).utilization,
+            0.5
+        );
+        assert_eq!(BlockGasStats::new(1, 0, 0).utilization, 0.0);
+    }
+}
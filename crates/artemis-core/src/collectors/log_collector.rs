@@ -3,18 +3,59 @@ use alloy::providers::Provider;
 use alloy::rpc::types::eth::{Filter, Log};
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::{FutureExt, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::warn;
+
+/// The logs of a single subscription.
+type LogStream = Pin<Box<dyn Stream<Item = Log> + Send>>;
 
 /// A collector that listens for new blockchain event logs based on a [Filter](Filter),
 /// and generates a stream of [events](Log).
+///
+/// The filter can be replaced while the collector runs, through
+/// [update_filter](Self::update_filter) or a [LogFilterHandle]. The stream then
+/// re-subscribes with the new filter, and once the new subscription is in
+/// place yields the logs the old one already received before dropping it. Logs
+/// matching both filters may therefore be yielded twice, while logs still in
+/// flight to the old subscription when it's dropped are missed.
 pub struct LogCollector<M> {
     provider: Arc<M>,
-    filter: Filter,
+    filter: LogFilterHandle,
+}
+
+/// A shared handle to the filter of a [LogCollector], for updating it after
+/// the collector has been handed to the engine.
+#[derive(Debug, Clone)]
+pub struct LogFilterHandle(Arc<watch::Sender<Filter>>);
+
+impl LogFilterHandle {
+    /// Replace the filter, re-subscribing every stream of the collector.
+    pub fn update_filter(&self, filter: Filter) {
+        self.0.send_replace(filter);
+    }
 }
 
 impl<M> LogCollector<M> {
     pub fn new(provider: Arc<M>, filter: Filter) -> Self {
-        Self { provider, filter }
+        Self {
+            provider,
+            filter: LogFilterHandle(Arc::new(watch::channel(filter).0)),
+        }
+    }
+
+    /// Replace the filter, re-subscribing every stream of the collector.
+    pub fn update_filter(&self, filter: Filter) {
+        self.filter.update_filter(filter);
+    }
+
+    /// A handle for updating the filter.
+    pub fn filter_handle(&self) -> LogFilterHandle {
+        self.filter.clone()
     }
 }
 
@@ -26,7 +67,120 @@ where
     M: Provider + Send + Sync + 'static,
 {
     async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, Log>> {
-        let stream = self.provider.subscribe_logs(&self.filter).await?;
-        Ok(Box::pin(stream.into_stream()))
+        let mut filter = self.filter.0.subscribe();
+        let initial = filter.borrow_and_update().clone();
+        let logs = self.provider.subscribe_logs(&initial).await?.into_stream();
+        let provider = self.provider.clone();
+        let subscribe = move |filter: Filter| {
+            let provider = provider.clone();
+            async move {
+                let logs = provider.subscribe_logs(&filter).await?.into_stream();
+                Ok::<LogStream, anyhow::Error>(logs.boxed())
+            }
+        };
+        Ok(Box::pin(follow_filter(logs.boxed(), filter, subscribe)))
+    }
+}
+
+/// Yield the logs from `logs`, re-subscribing with `subscribe` whenever
+/// `filter` changes. The logs the old subscription already received are
+/// yielded before the new subscription's. If re-subscribing fails, the old
+/// subscription is kept.
+fn follow_filter<F, Fut>(
+    logs: LogStream,
+    filter: watch::Receiver<Filter>,
+    subscribe: F,
+) -> impl Stream<Item = Log> + Send + 'static
+where
+    F: Fn(Filter) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<LogStream>> + Send + 'static,
+{
+    let subscribe = Arc::new(subscribe);
+    futures::stream::unfold(
+        (logs, filter, true, VecDeque::new()),
+        move |(mut logs, mut filter, mut watching, mut drained)| {
+            let subscribe = subscribe.clone();
+            async move {
+                loop {
+                    if let Some(log) = drained.pop_front() {
+                        return Some((log, (logs, filter, watching, drained)));
+                    }
+                    tokio::select! {
+                        log = logs.next() => {
+                            return log.map(|log| (log, (logs, filter, watching, drained)));
+                        }
+                        changed = filter.changed(), if watching => {
+                            if changed.is_err() {
+                                // The collector and every handle are gone, so the
+                                // filter is final.
+                                watching = false;
+                                continue;
+                            }
+                            let updated = filter.borrow_and_update().clone();
+                            match subscribe(updated).await {
+                                Ok(subscription) => {
+                                    while let Some(Some(log)) = logs.next().now_or_never() {
+                                        drained.push_back(log);
+                                    }
+                                    logs = subscription;
+                                }
+                                Err(e) => {
+                                    warn!(
+                                        "failed to resubscribe to logs, keeping the old filter: {e}"
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    fn log(block_number: u64) -> Log {
+        Log {
+            block_number: Some(block_number),
+            ..Default::default()
+        }
+    }
+
+    /// A subscription yielding `blocks`, then staying open.
+    fn subscription(blocks: Vec<u64>) -> LogStream {
+        futures::stream::iter(blocks.into_iter().map(log))
+            .chain(futures::stream::pending())
+            .boxed()
+    }
+
+    #[tokio::test]
+    async fn update_filter_resubscribes_after_draining_old_logs() {
+        let old = Filter::new().address(Address::repeat_byte(1));
+        let new = Filter::new().address(Address::repeat_byte(2));
+        let handle = LogFilterHandle(Arc::new(watch::channel(old).0));
+        let subscribed = new.clone();
+        let logs = follow_filter(
+            subscription(vec![1, 2]),
+            handle.0.subscribe(),
+            move |filter| {
+                assert_eq!(filter, subscribed);
+                async { Ok(subscription(vec![3])) }
+            },
+        );
+        let mut logs = Box::pin(logs);
+
+        handle.update_filter(new);
+        let blocks: Vec<_> = logs
+            .by_ref()
+            .take(3)
+            .map(|log| log.block_number.unwrap())
+            .collect()
+            .await;
+        assert_eq!(blocks, vec![1, 2, 3]);
+        assert!(logs.next().now_or_never().is_none());
     }
 }
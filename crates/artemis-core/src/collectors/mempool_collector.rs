@@ -1,35 +1,92 @@
+use crate::metrics::METRICS;
 use crate::types::{Collector, CollectorStream};
 use alloy::providers::Provider;
 use alloy::rpc::types::eth::Transaction;
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::StreamExt;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{trace, warn};
 
 /// A collector that listens for new transactions in the mempool, and generates a stream of
 /// [events](Transaction) which contain the transaction.
 pub struct MempoolCollector<M> {
     provider: Arc<M>,
+    /// Delay before the first resubscribe attempt after the subscription drops.
+    initial_backoff: Duration,
+    /// Upper bound of the resubscribe delay, which doubles on each failure.
+    max_backoff: Duration,
 }
 
 impl<M> MempoolCollector<M> {
     pub fn new(provider: Arc<M>) -> Self {
-        Self { provider }
+        Self {
+            provider,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
     }
 }
 
 /// Implementation of the [Collector](Collector) trait for the [MempoolCollector](MempoolCollector).
-/// This implementation subscribes to pending transactions via Alloy's pubsub support.
+/// This implementation subscribes to pending transactions via Alloy's pubsub support, and
+/// resubscribes with exponential backoff whenever the subscription ends or fails.
 #[async_trait]
 impl<M> Collector<Transaction> for MempoolCollector<M>
 where
     M: Provider + Send + Sync + 'static,
 {
     async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, Transaction>> {
-        let stream = self
+        // Subscribe once up front so that a bad provider fails fast.
+        let mut stream = self
             .provider
             .subscribe_full_pending_transactions()
             .await?
             .into_stream();
-        Ok(Box::pin(stream))
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let provider = self.provider.clone();
+        let (initial_backoff, max_backoff) = (self.initial_backoff, self.max_backoff);
+
+        tokio::spawn(async move {
+            loop {
+                while let Some(transaction) = stream.next().await {
+                    if tx.send(transaction).is_err() {
+                        trace!("mempool event receiver dropped, stopping collector");
+                        return;
+                    }
+                }
+
+                warn!("pending transaction subscription ended; resubscribing");
+                let mut backoff = initial_backoff;
+                stream = loop {
+                    if tx.is_closed() {
+                        return;
+                    }
+                    tokio::time::sleep(backoff).await;
+                    match provider.subscribe_full_pending_transactions().await {
+                        Ok(subscription) => {
+                            METRICS.record_reconnect();
+                            break subscription.into_stream();
+                        }
+                        Err(e) => {
+                            warn!("failed to resubscribe to pending transactions: {e}");
+                            backoff = (backoff * 2).min(max_backoff);
+                        }
+                    }
+                };
+            }
+        });
+
+        Ok(Box::pin(UnboundedReceiverStream::new(rx)))
     }
 }
@@ -1,18 +1,181 @@
+use crate::metrics::METRICS;
+use crate::mevshare::Event;
 use crate::types::{Collector, CollectorStream, Events, MEV_SHARE};
+use crate::util::full_jitter;
 use anyhow::Result;
 use async_trait::async_trait;
 use futures::StreamExt;
-use tokio::sync::mpsc;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, watch};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{trace, warn};
 
+/// Health of a [MevShareCollector](MevShareCollector)'s SSE feed, shared with
+/// monitoring via an `Arc`.
+#[derive(Debug, Default)]
+pub struct MevShareHealth {
+    /// Unix time in milliseconds of the last parsed event, or 0 if none.
+    last_event_ms: AtomicU64,
+    reconnects: AtomicU64,
+    parse_errors: AtomicU64,
+}
+
+impl MevShareHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time the last event was successfully parsed, if any.
+    pub fn last_event_at(&self) -> Option<SystemTime> {
+        match self.last_event_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(UNIX_EPOCH + Duration::from_millis(ms)),
+        }
+    }
+
+    /// Number of times the SSE stream has been reconnected.
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Number of payloads that couldn't be parsed as an [Event].
+    pub fn parse_errors(&self) -> u64 {
+        self.parse_errors.load(Ordering::Relaxed)
+    }
+
+    /// Whether no event has been seen within `max_gap`. A feed that has never
+    /// produced an event is considered stale.
+    pub fn is_stale(&self, max_gap: Duration) -> bool {
+        self.last_event_at()
+            .and_then(|at| at.elapsed().ok())
+            .is_none_or(|gap| gap > max_gap)
+    }
+
+    fn record_event(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.last_event_ms.store(now, Ordering::Relaxed);
+    }
+
+    fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_parse_error(&self) {
+        self.parse_errors.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Maximum number of characters of an unparseable payload that are logged.
+const MAX_LOGGED_PAYLOAD: usize = 512;
+
+/// `payload` cut down to at most `max` characters for logging.
+fn truncate_payload(payload: &str, max: usize) -> String {
+    match payload.char_indices().nth(max) {
+        Some((end, _)) => format!("{}... ({} bytes)", &payload[..end], payload.len()),
+        None => payload.to_string(),
+    }
+}
+
+/// Log a payload that failed to parse as an [Event], at warn if `loud`.
+fn log_parse_error(err: &serde_json::Error, payload: &serde_json::Value, loud: bool) {
+    let payload = truncate_payload(&payload.to_string(), MAX_LOGGED_PAYLOAD);
+    if loud {
+        warn!("failed to parse MEV-share event: {err}; payload: {payload}");
+    } else {
+        trace!("failed to parse MEV-share event: {err}; payload: {payload}");
+    }
+}
+
 /// A collector that streams from MEV-Share SSE endpoint
 /// and generates [events](Event), which return tx hash, logs, and bundled txs.
-pub struct MevShareCollector;
+///
+/// Each stream is fed by a background task, which stops as soon as the stream
+/// is dropped or the collector is [closed](Collector::close), even while the
+/// connection is stalled. A closed collector's streams end immediately.
+///
+/// Endpoints may compress the stream with gzip or brotli, per its
+/// `Content-Encoding`.
+pub struct MevShareCollector {
+    /// URL of the MEV-Share SSE endpoint.
+    url: String,
+    /// Optional health metrics updated as events arrive.
+    health: Option<Arc<MevShareHealth>>,
+    /// Whether payloads that fail to parse are logged at warn rather than
+    /// trace.
+    warn_on_parse_errors: bool,
+    /// Delay before the first reconnect attempt after the stream drops.
+    initial_backoff: Duration,
+    /// Upper bound of the reconnect delay, which doubles on each failure.
+    max_backoff: Duration,
+    /// Whether each reconnect delay is drawn at random from zero up to the
+    /// backoff, so that many clients dropped at once don't reconnect in step.
+    jitter: bool,
+    /// Set once the collector is [closed](Collector::close), stopping every
+    /// stream task.
+    closed: watch::Sender<bool>,
+}
 
 impl MevShareCollector {
+    /// Create a collector streaming from the mainnet MEV-Share endpoint.
     pub fn new() -> Self {
-        Self
+        Self::with_url(MEV_SHARE)
+    }
+
+    /// Create a collector streaming from the given MEV-Share endpoint, e.g.
+    /// [MEV_SHARE_SEPOLIA](crate::types::MEV_SHARE_SEPOLIA).
+    pub fn with_url(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            health: None,
+            warn_on_parse_errors: false,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            closed: watch::channel(false).0,
+        }
+    }
+
+    /// Report the feed's health to `health` as events arrive.
+    pub fn with_health(mut self, health: Arc<MevShareHealth>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Log payloads that fail to parse as an [Event] at warn, along with the
+    /// start of the raw payload, e.g. to notice relay schema changes. Parse
+    /// errors are counted by [MevShareHealth] either way. Data that isn't
+    /// valid JSON at all is still dropped by the SSE client unlogged.
+    pub fn with_parse_error_warnings(mut self) -> Self {
+        self.warn_on_parse_errors = true;
+        self
+    }
+
+    /// Wait `initial` before reconnecting a dropped stream, doubling the delay
+    /// on each failed attempt up to `max`, which is raised to `initial` if
+    /// lower. Defaults to 500ms and 30s.
+    pub fn with_reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Whether to randomize reconnect delays, on by default. Turn it off for
+    /// deterministic delays, e.g. in tests.
+    pub fn with_reconnect_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+}
+
+impl Default for MevShareCollector {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -22,41 +185,112 @@ impl MevShareCollector {
 impl Collector<Events> for MevShareCollector {
     async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, Events>> {
         let (tx, rx) = mpsc::unbounded_channel();
+        let url = self.url.clone();
+        let health = self.health.clone();
+        let warn_on_parse_errors = self.warn_on_parse_errors;
+        let (initial_backoff, max_backoff) = (self.initial_backoff, self.max_backoff);
+        let jitter = self.jitter;
+        let mut closed = self.closed.subscribe();
+        let receiver_dropped = tx.clone();
+        // Decode gzip and brotli compressed streams before the SSE client
+        // scans them for events.
+        let http = reqwest::Client::builder().gzip(true).brotli(true).build()?;
 
-        tokio::spawn(async move {
+        let run = async move {
+            let mut backoff = initial_backoff;
             loop {
-                let client = mev_share_sse::EventClient::default().with_max_retries(u64::MAX);
-                let mut stream = client.events(MEV_SHARE).await.unwrap();
-
-                while let Some(event) = stream.next().await {
-                    match event {
-                        Ok(event) => {
-                            if tx.send(Events::MevShareEvent(event)).is_err() {
-                                trace!("all MEV-share receivers dropped, stopping stream");
-                                return;
-                            }
+                let client =
+                    mev_share_sse::EventClient::new(http.clone()).with_max_retries(u64::MAX);
+                // Events are parsed here rather than by the client, so that
+                // payloads that don't match the schema are surfaced.
+                match client.subscribe::<serde_json::Value>(&url).await {
+                    Ok(mut stream) => {
+                        while let Some(payload) = stream.next().await {
+                            let event = payload.map(|payload| {
+                                Event::deserialize(&payload).map_err(|err| (err, payload))
+                            });
+                            match event {
+                                Ok(Err((err, payload))) => {
+                                    if let Some(health) = &health {
+                                        health.record_parse_error();
+                                    }
+                                    log_parse_error(&err, &payload, warn_on_parse_errors);
+                                }
+                                Ok(Ok(event)) => {
+                                    if let Some(health) = &health {
+                                        health.record_event();
+                                    }
+                                    backoff = initial_backoff;
+                                    if tx.send(Events::MevShareEvent(event)).is_err() {
+                                        trace!("all MEV-share receivers dropped, stopping stream");
+                                        return;
+                                    }
+                                }
+                                Err(err) => {
+                                    warn!("MEV-share SSE stream error: {err}");
+                                    break;
+                                }
+                            };
                         }
-                        Err(err) => {
-                            warn!("MEV-share SSE stream error: {err}");
-                            break;
-                        }
-                    };
+                    }
+                    Err(err) => warn!("failed to subscribe to MEV-share events: {err}"),
                 }
                 if tx.is_closed() {
                     trace!("MEV-share event receiver dropped, stopping collector loop");
                     break;
                 }
+                let delay = if jitter {
+                    full_jitter(backoff)
+                } else {
+                    backoff
+                };
+                tokio::time::sleep(delay).await;
+                backoff = (backoff * 2).min(max_backoff);
+                if let Some(health) = &health {
+                    health.record_reconnect();
+                }
+                METRICS.record_reconnect();
+            }
+        };
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = run => {}
+                _ = receiver_dropped.closed() => {
+                    trace!("MEV-share event receiver dropped, stopping collector loop");
+                }
+                _ = closed.wait_for(|closed| *closed) => {
+                    trace!("MEV-share collector closed, stopping collector loop");
+                }
             }
         });
 
         Ok(Box::pin(UnboundedReceiverStream::new(rx)))
     }
+
+    async fn close(&self) {
+        self.closed.send_replace(true);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn truncates_long_payloads() {
+        assert_eq!(truncate_payload("{}", 4), "{}");
+        assert_eq!(truncate_payload("ééééé", 2), "éé... (10 bytes)");
+    }
+
+    #[tokio::test]
+    async fn close_ends_streams() {
+        let collector = MevShareCollector::with_url("http://127.0.0.1:1");
+        let mut stream = collector.get_event_stream().await.unwrap();
+        collector.close().await;
+        let next = tokio::time::timeout(Duration::from_secs(1), stream.next()).await;
+        assert!(matches!(next, Ok(None)));
+    }
+
     #[tokio::test]
     async fn mevshare_collector() {
         let collector = MevShareCollector::new();
@@ -65,9 +299,11 @@ mod tests {
             .await
             .expect("failed to get event");
 
-        while let Some(event) = stream.next().await {
+        // the stream reconnects forever without network, so don't wait on it
+        if let Ok(Some(event)) = tokio::time::timeout(Duration::from_secs(10), stream.next()).await
+        {
             dbg!(&event);
-            break;
         }
+        collector.close().await;
     }
 }
@@ -5,6 +5,21 @@
 /// This collector listens to a stream of new blocks.
 pub mod block_collector;
 
+/// This collector listens to a stream of new blocks, and tracks their base fee trend.
+pub mod base_fee_collector;
+
+/// This collector listens to pending transactions sent to a set of addresses.
+pub mod filtered_mempool_collector;
+
+/// This collector listens to a stream of new blocks, and emits them once final.
+pub mod finalized_block_collector;
+
+/// This collector listens to a stream of new blocks, and reports their gas usage.
+pub mod gas_usage_collector;
+
+/// This collector replays events recorded in a JSON-lines file.
+pub mod file_replay_collector;
+
 /// This collector listens to a stream of new event logs.
 pub mod log_collector;
 
@@ -12,3 +27,18 @@ pub mod log_collector;
 pub mod mempool_collector;
 
 pub mod mevshare_collector;
+
+/// This collector merges several MEV-Share endpoints, deduplicating events.
+pub mod multi_mevshare_collector;
+
+/// This collector polls a MEV-Boost relay for the builders winning each block.
+pub mod builder_stats_collector;
+
+/// This collector wraps another collector and coalesces its events by key.
+pub mod coalesce_collector;
+
+/// This collector wraps another collector and limits the rate of its events.
+pub mod throttle_collector;
+
+/// This collector listens for uniswap v2 and v3 swaps and decodes them.
+pub mod uniswap_swap_collector;
@@ -0,0 +1,114 @@
+use crate::collectors::mevshare_collector::MevShareCollector;
+use crate::types::{Collector, CollectorStream, Events};
+use alloy::primitives::B256;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::{HashSet, VecDeque};
+
+/// Default number of recent event hashes remembered for deduplication.
+const DEFAULT_DEDUP_CAPACITY: usize = 4096;
+
+/// A collector that streams from several MEV-Share SSE endpoints at once, and
+/// emits each [event](crate::mevshare::Event) once, from whichever endpoint
+/// delivered it first. Every endpoint reconnects independently.
+pub struct MultiMevShareCollector {
+    collectors: Vec<MevShareCollector>,
+    /// Number of recent event hashes remembered for deduplication.
+    dedup_capacity: usize,
+}
+
+impl MultiMevShareCollector {
+    pub fn new(urls: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            collectors: urls.into_iter().map(MevShareCollector::with_url).collect(),
+            dedup_capacity: DEFAULT_DEDUP_CAPACITY,
+        }
+    }
+
+    pub fn with_dedup_capacity(mut self, capacity: usize) -> Self {
+        self.dedup_capacity = capacity.max(1);
+        self
+    }
+}
+
+/// A bounded set of recently seen hashes, evicting the oldest when full.
+struct RecentHashes {
+    capacity: usize,
+    order: VecDeque<B256>,
+    seen: HashSet<B256>,
+}
+
+impl RecentHashes {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            seen: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Remember `hash`, returning whether it's new.
+    fn insert(&mut self, hash: B256) -> bool {
+        if !self.seen.insert(hash) {
+            return false;
+        }
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.order.push_back(hash);
+        true
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [MultiMevShareCollector](MultiMevShareCollector).
+#[async_trait]
+impl Collector<Events> for MultiMevShareCollector {
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, Events>> {
+        let mut streams = Vec::with_capacity(self.collectors.len());
+        for collector in &self.collectors {
+            streams.push(collector.get_event_stream().await?);
+        }
+
+        let mut recent = RecentHashes::new(self.dedup_capacity);
+        let stream = futures::stream::select_all(streams).filter(move |event| {
+            let is_new = match event {
+                Events::MevShareEvent(event) => recent.insert(event.hash),
+                _ => true,
+            };
+            futures::future::ready(is_new)
+        });
+        Ok(Box::pin(stream))
+    }
+
+    async fn close(&self) {
+        for collector in &self.collectors {
+            collector.close().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_hashes_forget_oldest() {
+        let mut recent = RecentHashes::new(2);
+        let (a, b, c) = (
+            B256::repeat_byte(1),
+            B256::repeat_byte(2),
+            B256::repeat_byte(3),
+        );
+        assert!(recent.insert(a));
+        assert!(!recent.insert(a));
+        assert!(recent.insert(b));
+        assert!(recent.insert(c));
+        // `a` was evicted to make room for `c`.
+        assert!(recent.insert(a));
+        assert!(!recent.insert(c));
+    }
+}
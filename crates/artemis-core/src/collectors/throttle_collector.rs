@@ -0,0 +1,134 @@
+use crate::types::{Collector, CollectorStream};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// What a [ThrottleCollector](ThrottleCollector) does with events that arrive
+/// faster than its interval allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottlePolicy {
+    /// Emit the first event of each interval immediately, and drop the rest.
+    DropExcess,
+    /// Hold on to the most recent event, and emit it at the end of each
+    /// interval.
+    KeepLatest,
+}
+
+/// A collector that wraps another [Collector](Collector) and emits at most one
+/// event per interval, handling the excess according to a [ThrottlePolicy].
+pub struct ThrottleCollector<E> {
+    collector: Box<dyn Collector<E>>,
+    interval: Duration,
+    policy: ThrottlePolicy,
+}
+
+impl<E> ThrottleCollector<E> {
+    pub fn new(
+        collector: Box<dyn Collector<E>>,
+        interval: Duration,
+        policy: ThrottlePolicy,
+    ) -> Self {
+        Self {
+            collector,
+            interval,
+            policy,
+        }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [ThrottleCollector](ThrottleCollector).
+#[async_trait]
+impl<E> Collector<E> for ThrottleCollector<E>
+where
+    E: Send + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, E>> {
+        let mut stream: CollectorStream<'static, E> = self.collector.get_event_stream().await?;
+        let interval = self.interval;
+
+        match self.policy {
+            ThrottlePolicy::DropExcess => {
+                let mut last_emitted: Option<Instant> = None;
+                let stream = stream.filter(move |_| {
+                    let now = Instant::now();
+                    if last_emitted.is_some_and(|last| now.duration_since(last) < interval) {
+                        return false;
+                    }
+                    last_emitted = Some(now);
+                    true
+                });
+                Ok(Box::pin(stream))
+            }
+            ThrottlePolicy::KeepLatest => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                tokio::spawn(async move {
+                    let mut ticker = tokio::time::interval(interval);
+                    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                    let mut latest = None;
+                    loop {
+                        tokio::select! {
+                            event = stream.next() => match event {
+                                Some(event) => latest = Some(event),
+                                None => {
+                                    // flush the last held event before ending the stream
+                                    if let Some(event) = latest.take() {
+                                        let _ = tx.send(event);
+                                    }
+                                    break;
+                                }
+                            },
+                            _ = ticker.tick() => {
+                                if let Some(event) = latest.take()
+                                    && tx.send(event).is_err()
+                                {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                });
+                Ok(Box::pin(UnboundedReceiverStream::new(rx)))
+            }
+        }
+    }
+
+    async fn close(&self) {
+        self.collector.close().await
+    }
+
+    fn name(&self) -> &str {
+        self.collector.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::ScriptedCollector;
+
+    async fn throttled(policy: ThrottlePolicy) -> Vec<u64> {
+        let collector = Box::new(ScriptedCollector::new(vec![1u64, 2, 3]));
+        ThrottleCollector::new(collector, Duration::from_secs(60), policy)
+            .get_event_stream()
+            .await
+            .unwrap()
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn throttles_bursts_by_policy() {
+        assert_eq!(throttled(ThrottlePolicy::DropExcess).await, vec![1]);
+
+        // The interval's first tick may emit the first event, but the rest
+        // of the burst collapses into the latest one.
+        let events = throttled(ThrottlePolicy::KeepLatest).await;
+        assert_eq!(events.last(), Some(&3));
+        assert!(!events.contains(&2));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::collectors::log_collector::LogCollector;
+use crate::types::{Collector, CollectorStream};
+use alloy::primitives::{Address, I256, Log as LogPrimitive, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::eth::Filter;
+use alloy::sol;
+use alloy::sol_types::SolEvent;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+sol! {
+    /// The `Swap` event of a uniswap v2 pair.
+    interface IUniswapV2Pair {
+        event Swap(
+            address indexed sender,
+            uint256 amount0In,
+            uint256 amount1In,
+            uint256 amount0Out,
+            uint256 amount1Out,
+            address indexed to
+        );
+    }
+
+    /// The `Swap` event of a uniswap v3 pool.
+    interface IUniswapV3Pool {
+        event Swap(
+            address indexed sender,
+            address indexed recipient,
+            int256 amount0,
+            int256 amount1,
+            uint160 sqrtPriceX96,
+            uint128 liquidity,
+            int24 tick
+        );
+    }
+}
+
+/// A decoded uniswap v2 or v3 swap. Amounts are signed from the pool's point
+/// of view: positive amounts flowed into the pool, negative amounts out of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub pool: Address,
+    pub amount0: I256,
+    pub amount1: I256,
+    pub sender: Address,
+    pub recipient: Address,
+}
+
+/// Decode a uniswap v2 or v3 `Swap` log, returning `None` for any other log.
+pub fn decode_swap_event(log: &LogPrimitive) -> Option<SwapEvent> {
+    if let Ok(swap) = IUniswapV3Pool::Swap::decode_log_data(&log.data) {
+        return Some(SwapEvent {
+            pool: log.address,
+            amount0: swap.amount0,
+            amount1: swap.amount1,
+            sender: swap.sender,
+            recipient: swap.recipient,
+        });
+    }
+
+    let swap = IUniswapV2Pair::Swap::decode_log_data(&log.data).ok()?;
+    let net = |amount_in: U256, amount_out: U256| {
+        I256::try_from(amount_in)
+            .ok()?
+            .checked_sub(I256::try_from(amount_out).ok()?)
+    };
+    Some(SwapEvent {
+        pool: log.address,
+        amount0: net(swap.amount0In, swap.amount0Out)?,
+        amount1: net(swap.amount1In, swap.amount1Out)?,
+        sender: swap.sender,
+        recipient: swap.to,
+    })
+}
+
+/// A collector that listens for `Swap` events on a set of uniswap v2 and v3
+/// pools, and generates a stream of decoded [events](SwapEvent).
+pub struct UniswapSwapCollector<M> {
+    inner: LogCollector<M>,
+}
+
+impl<M> UniswapSwapCollector<M> {
+    pub fn new(provider: Arc<M>, pools: impl IntoIterator<Item = Address>) -> Self {
+        let filter = Filter::new()
+            .address(pools.into_iter().collect::<Vec<_>>())
+            .event_signature(vec![
+                IUniswapV2Pair::Swap::SIGNATURE_HASH,
+                IUniswapV3Pool::Swap::SIGNATURE_HASH,
+            ]);
+        Self {
+            inner: LogCollector::new(provider, filter),
+        }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the
+/// [UniswapSwapCollector](UniswapSwapCollector). Logs that fail to decode are
+/// dropped.
+#[async_trait]
+impl<M> Collector<SwapEvent> for UniswapSwapCollector<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, SwapEvent>> {
+        let stream = self.inner.get_event_stream().await?;
+        let stream = stream.filter_map(|log| decode_swap_event(&log.inner));
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    #[test]
+    fn decodes_v2_swap_as_net_amounts() {
+        let pool = address!("0x0000000000000000000000000000000000000001");
+        let swap = IUniswapV2Pair::Swap {
+            sender: Address::ZERO,
+            amount0In: U256::from(100u64),
+            amount1In: U256::ZERO,
+            amount0Out: U256::ZERO,
+            amount1Out: U256::from(40u64),
+            to: pool,
+        };
+        let log = LogPrimitive {
+            address: pool,
+            data: swap.encode_log_data(),
+        };
+
+        let event = decode_swap_event(&log).unwrap();
+        assert_eq!(event.pool, pool);
+        assert_eq!(event.amount0, I256::try_from(100).unwrap());
+        assert_eq!(event.amount1, I256::try_from(-40).unwrap());
+        assert_eq!(event.recipient, pool);
+        assert_eq!(decode_swap_event(&LogPrimitive::default()), None);
+    }
+}
@@ -1,12 +1,72 @@
-use tokio::sync::broadcast::{self, Sender, error::RecvError};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast::{
+    self, Receiver, Sender,
+    error::{RecvError, TryRecvError},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc, watch};
 use tokio::task::JoinSet;
 use tokio_stream::StreamExt;
-use tracing::{error, info};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{Instrument, Span, debug, error, info, info_span, warn};
 
+use crate::executors::error::ExecutorError;
+use crate::metrics::{METRICS, MetricsSnapshot};
 use crate::types::{Collector, Executor, Strategy};
 
+/// Ranks actions so that higher-priority actions are executed first.
+pub type ActionPriority<A> = Arc<dyn Fn(&A) -> u8 + Send + Sync>;
+
+/// An action along with the span of the event it was produced for, so that
+/// executor logs are grouped with the event's.
+type TracedAction<A> = (A, Span);
+
+/// An event as broadcast to the strategies, along with the `event` span opened
+/// when it was collected. With per-collector channels, it holds one of the
+/// permits bounding the events in flight, which is released once every
+/// strategy has processed the event.
+#[derive(Clone)]
+struct BroadcastEvent<E> {
+    event: E,
+    span: Span,
+    permit: Option<Arc<OwnedSemaphorePermit>>,
+}
+
+/// An action an executor failed to execute, along with the error.
+#[derive(Debug)]
+pub struct FailedAction<A> {
+    pub action: A,
+    /// Name of the executor that failed.
+    pub executor: String,
+    pub error: anyhow::Error,
+}
+
+/// Correlation id of the next event collected.
+static NEXT_EVENT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Open the `event` span for an event just collected by `collector`, under
+/// which every strategy processes it and every executor executes its actions.
+fn collected_event_span(collector: &str) -> Span {
+    let span = info_span!(
+        "event",
+        id = NEXT_EVENT_ID.fetch_add(1, Ordering::Relaxed),
+        collector
+    );
+    span.in_scope(|| debug!("collected event"));
+    span
+}
+
 /// The main engine of Artemis. This struct is responsible for orchestrating the
 /// data flow between collectors, strategies, and executors.
+///
+/// Every strategy receives every event, and every executor every action. By
+/// default, a strategy that falls more than the event channel capacity behind
+/// skips the oldest events, logging and counting the drop, rather than holding
+/// back the others. With
+/// [per-collector channels](Self::with_collector_channel_capacity), events are
+/// never dropped: the slowest strategy paces the collectors instead.
 pub struct Engine<E, A> {
     /// The set of collectors that the engine will use to collect events.
     collectors: Vec<Box<dyn Collector<E>>>,
@@ -22,6 +82,81 @@ pub struct Engine<E, A> {
 
     /// The capacity of the action channel.
     action_channel_capacity: usize,
+
+    /// The capacity of each collector's own event channel. When set, every
+    /// collector gets a separate bounded channel, and these are interleaved
+    /// into the event channel so a noisy collector can't starve the others.
+    /// Events are then only taken off a collector's channel once the
+    /// strategies have room for them.
+    collector_channel_capacity: Option<usize>,
+
+    /// The number of times a failed strategy sync is retried before giving up.
+    sync_state_retries: usize,
+
+    /// The delay before the first sync retry, doubled on each further attempt.
+    sync_state_backoff: Duration,
+
+    /// How long an executor may take to execute a single action before the
+    /// engine fails it and moves on to the next one.
+    executor_timeout: Option<Duration>,
+
+    /// Signals the engine to shut down once it's set to `true`. Collectors
+    /// and strategies stop, and executors stop once every action already
+    /// produced has been executed.
+    shutdown: Option<watch::Receiver<bool>>,
+
+    /// Whether strategies process the events already buffered in the event
+    /// channel before stopping on shutdown, within `drain_deadline`. Defaults
+    /// to `false`: buffered events are dropped.
+    drain_on_shutdown: bool,
+
+    /// How long strategies may spend draining buffered events on shutdown.
+    /// Defaults to 5 seconds.
+    drain_deadline: Duration,
+
+    /// Ranks the actions returned for a single event. Higher-priority actions
+    /// are sent to the executors first; ties keep the strategy's order.
+    action_priority: Option<ActionPriority<A>>,
+
+    /// Receives the actions executors fail to execute, if set. Otherwise
+    /// failed actions are dropped after logging.
+    dead_letters: Option<mpsc::Sender<FailedAction<A>>>,
+
+    /// Carries the strategies added through a [StrategyHandle] once the engine
+    /// runs. Only created once a handle is requested.
+    strategy_channel: Option<StrategyChannel<E, A>>,
+}
+
+/// The channel strategies added at runtime are sent through.
+type StrategyChannel<E, A> = (
+    mpsc::UnboundedSender<Box<dyn Strategy<E, A>>>,
+    mpsc::UnboundedReceiver<Box<dyn Strategy<E, A>>>,
+);
+
+/// A handle for adding strategies to an [Engine] after it has started. Added
+/// strategies are synced, then see every event collected from then on.
+pub struct StrategyHandle<E, A>(mpsc::UnboundedSender<Box<dyn Strategy<E, A>>>);
+
+impl<E, A> StrategyHandle<E, A> {
+    /// Add a strategy to the running engine. Fails if the engine has stopped
+    /// taking new strategies.
+    pub fn add_strategy(&self, strategy: Box<dyn Strategy<E, A>>) -> anyhow::Result<()> {
+        self.0
+            .send(strategy)
+            .map_err(|_| anyhow::anyhow!("engine is no longer accepting strategies"))
+    }
+}
+
+impl<E, A> Clone for StrategyHandle<E, A> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E, A> std::fmt::Debug for StrategyHandle<E, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StrategyHandle").finish_non_exhaustive()
+    }
 }
 
 impl<E, A> Engine<E, A> {
@@ -32,6 +167,16 @@ impl<E, A> Engine<E, A> {
             executors: vec![],
             event_channel_capacity: 512,
             action_channel_capacity: 512,
+            collector_channel_capacity: None,
+            sync_state_retries: 3,
+            sync_state_backoff: Duration::from_secs(1),
+            executor_timeout: None,
+            shutdown: None,
+            drain_on_shutdown: false,
+            drain_deadline: Duration::from_secs(5),
+            action_priority: None,
+            dead_letters: None,
+            strategy_channel: None,
         }
     }
 
@@ -44,6 +189,61 @@ impl<E, A> Engine<E, A> {
         self.action_channel_capacity = capacity;
         self
     }
+
+    /// Give each collector its own channel of `capacity` events, applying
+    /// backpressure per collector instead of dropping events that strategies
+    /// can't keep up with.
+    pub fn with_collector_channel_capacity(mut self, capacity: usize) -> Self {
+        self.collector_channel_capacity = Some(capacity);
+        self
+    }
+
+    pub fn with_sync_state_retries(mut self, retries: usize) -> Self {
+        self.sync_state_retries = retries;
+        self
+    }
+
+    pub fn with_sync_state_backoff(mut self, backoff: Duration) -> Self {
+        self.sync_state_backoff = backoff;
+        self
+    }
+
+    pub fn with_executor_timeout(mut self, timeout: Duration) -> Self {
+        self.executor_timeout = Some(timeout);
+        self
+    }
+
+    /// Shut the engine down once `shutdown` is set to `true`.
+    pub fn with_shutdown(mut self, shutdown: watch::Receiver<bool>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// On shutdown, process the events already buffered before stopping,
+    /// giving up after `deadline`.
+    pub fn with_drain_on_shutdown(mut self, deadline: Duration) -> Self {
+        self.drain_on_shutdown = true;
+        self.drain_deadline = deadline;
+        self
+    }
+
+    /// Send the actions returned for each event to the executors in order of
+    /// descending `priority`, e.g. so bundle submissions go before logging.
+    pub fn with_action_priority<F>(mut self, priority: F) -> Self
+    where
+        F: Fn(&A) -> u8 + Send + Sync + 'static,
+    {
+        self.action_priority = Some(Arc::new(priority));
+        self
+    }
+
+    /// Send every action an executor fails to execute, with the error, to
+    /// `dead_letters`, e.g. to persist or retry it. Failed actions are dropped
+    /// with a warning if the channel is full.
+    pub fn with_dead_letters(mut self, dead_letters: mpsc::Sender<FailedAction<A>>) -> Self {
+        self.dead_letters = Some(dead_letters);
+        self
+    }
 }
 
 impl<E, A> Default for Engine<E, A> {
@@ -67,6 +267,16 @@ where
         self.strategies.push(strategy);
     }
 
+    /// A handle for adding strategies once the engine runs. The engine keeps
+    /// accepting them until every handle is dropped or shutdown is requested,
+    /// and executors keep running until then.
+    pub fn strategy_handle(&mut self) -> StrategyHandle<E, A> {
+        let (sender, _) = self
+            .strategy_channel
+            .get_or_insert_with(mpsc::unbounded_channel);
+        StrategyHandle(sender.clone())
+    }
+
     /// Adds an executor to be used by the engine.
     pub fn add_executor(&mut self, executor: Box<dyn Executor<A>>) {
         self.executors.push(executor);
@@ -76,82 +286,714 @@ where
     /// each collector, strategy, and executor. It will then orchestrate the
     /// data flow between them.
     pub async fn run(self) -> Result<JoinSet<()>, Box<dyn std::error::Error>> {
-        let (event_sender, _): (Sender<E>, _) = broadcast::channel(self.event_channel_capacity);
-        let (action_sender, _): (Sender<A>, _) = broadcast::channel(self.action_channel_capacity);
+        let (event_sender, _): (Sender<BroadcastEvent<E>>, _) =
+            broadcast::channel(self.event_channel_capacity);
+        let (action_sender, _): (Sender<TracedAction<A>>, _) =
+            broadcast::channel(self.action_channel_capacity);
 
         let mut set = JoinSet::new();
 
         // Spawn executors in separate threads.
         for executor in self.executors {
             let mut receiver = action_sender.subscribe();
-            set.spawn(async move {
-                info!("starting executor... ");
-                loop {
-                    match receiver.recv().await {
-                        Ok(action) => match executor.execute(action).await {
-                            Ok(_) => {}
-                            Err(e) => error!("error executing action: {}", e),
-                        },
-                        Err(RecvError::Closed) => {
-                            info!("action channel closed; stopping executor thread");
-                            break;
-                        }
-                        Err(RecvError::Lagged(skipped)) => {
-                            error!("executor lagged and skipped {} actions", skipped);
+            let executor_timeout = self.executor_timeout;
+            let dead_letters = self.dead_letters.clone();
+            let name = executor.name().to_string();
+            let span = info_span!("executor", component = name.as_str());
+            set.spawn(
+                async move {
+                    info!("starting executor {name}... ");
+                    loop {
+                        match receiver.recv().await {
+                            Ok((action, event_span)) => {
+                                // Tag the execution with this executor, under the event.
+                                let span = info_span!(
+                                    parent: &event_span,
+                                    "executor",
+                                    component = name.as_str()
+                                );
+                                let started = Instant::now();
+                                let retained = dead_letters.as_ref().map(|_| action.clone());
+                                let result = execute_with_timeout(
+                                    executor.as_ref(),
+                                    action,
+                                    executor_timeout,
+                                )
+                                .instrument(span.clone())
+                                .await;
+                                span.in_scope(|| match result {
+                                    Err(e) => {
+                                        error!("executor {name} failed to execute action: {}", e);
+                                        if let (Some(dead_letters), Some(action)) =
+                                            (&dead_letters, retained)
+                                        {
+                                            send_to_dead_letters(
+                                                dead_letters,
+                                                FailedAction {
+                                                    action,
+                                                    executor: name.clone(),
+                                                    error: e,
+                                                },
+                                            );
+                                        }
+                                    }
+                                    Ok(()) => {
+                                        debug!(
+                                            elapsed = ?started.elapsed(),
+                                            "executor {name} executed action"
+                                        );
+                                        METRICS.record_action_executed();
+                                    }
+                                });
+                            }
+                            Err(RecvError::Closed) => {
+                                info!("action channel closed; stopping executor {name}");
+                                break;
+                            }
+                            Err(RecvError::Lagged(skipped)) => {
+                                error!("executor {name} lagged and skipped {} actions", skipped);
+                            }
                         }
                     }
                 }
-            });
+                .instrument(span),
+            );
         }
 
         // Spawn strategies in separate threads.
         for mut strategy in self.strategies {
-            let mut event_receiver = event_sender.subscribe();
-            let action_sender = action_sender.clone();
-            strategy.sync_state().await?;
+            let event_receiver = event_sender.subscribe();
+            sync_state_with_retry(
+                strategy.as_mut(),
+                self.sync_state_retries,
+                self.sync_state_backoff,
+            )
+            .await?;
+            let span = info_span!("strategy", component = strategy.name());
+            set.spawn(
+                run_strategy(
+                    strategy,
+                    event_receiver,
+                    action_sender.clone(),
+                    self.shutdown.clone(),
+                    self.drain_on_shutdown.then_some(self.drain_deadline),
+                    self.action_priority.clone(),
+                )
+                .instrument(span),
+            );
+        }
 
+        // Wire in strategies added through a handle while the engine runs. Only
+        // a weak sender is kept, so the event channel still closes once the
+        // collectors stop.
+        if let Some((sender, mut added)) = self.strategy_channel {
+            drop(sender);
+            let events = event_sender.downgrade();
+            let action_sender = action_sender.clone();
+            let mut shutdown = self.shutdown.clone();
+            let (retries, backoff) = (self.sync_state_retries, self.sync_state_backoff);
+            let drain_deadline = self.drain_on_shutdown.then_some(self.drain_deadline);
+            let priority = self.action_priority.clone();
             set.spawn(async move {
-                info!("starting strategy... ");
+                let mut strategies = JoinSet::new();
                 loop {
-                    match event_receiver.recv().await {
-                        Ok(event) => {
-                            for action in strategy.process_event(event).await {
-                                match action_sender.send(action) {
-                                    Ok(_) => {}
-                                    Err(e) => {
-                                        error!("error sending action: {}", e);
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                        Err(RecvError::Closed) => {
-                            info!("event channel closed; stopping strategy thread");
-                            break;
-                        }
-                        Err(RecvError::Lagged(skipped)) => {
-                            error!("strategy lagged and skipped {} events", skipped);
-                        }
+                    let mut strategy = tokio::select! {
+                        strategy = added.recv() => match strategy {
+                            Some(strategy) => strategy,
+                            None => break,
+                        },
+                        _ = shutdown_requested(&mut shutdown) => break,
+                    };
+                    if let Err(e) = sync_state_with_retry(strategy.as_mut(), retries, backoff).await
+                    {
+                        error!("dropping added strategy {}: {}", strategy.name(), e);
+                        continue;
                     }
+                    let Some(event_receiver) = events.upgrade().map(|sender| sender.subscribe())
+                    else {
+                        warn!(
+                            "event channel closed; dropping added strategy {}",
+                            strategy.name()
+                        );
+                        break;
+                    };
+                    let span = info_span!("strategy", component = strategy.name());
+                    strategies.spawn(
+                        run_strategy(
+                            strategy,
+                            event_receiver,
+                            action_sender.clone(),
+                            shutdown.clone(),
+                            drain_deadline,
+                            priority.clone(),
+                        )
+                        .instrument(span),
+                    );
                 }
+                drop(action_sender);
+                while strategies.join_next().await.is_some() {}
             });
         }
 
         // Spawn collectors in separate threads.
+        let Some(collector_channel_capacity) = self.collector_channel_capacity else {
+            for collector in self.collectors {
+                let event_sender = event_sender.clone();
+                let mut shutdown = self.shutdown.clone();
+                let name = collector.name().to_string();
+                let span = info_span!("collector", component = name.as_str());
+                set.spawn(
+                    async move {
+                        info!("starting collector {name}... ");
+                        let mut event_stream = collector.get_event_stream().await.unwrap();
+                        while let Some(event) = tokio::select! {
+                            event = event_stream.next() => event,
+                            _ = shutdown_requested(&mut shutdown) => None,
+                        } {
+                            METRICS.record_collector_event(&name);
+                            match event_sender.send(BroadcastEvent {
+                                event,
+                                span: collected_event_span(&name),
+                                permit: None,
+                            }) {
+                                Ok(_) => {}
+                                Err(e) => error!("error sending event: {}", e),
+                            }
+                        }
+                        collector.close().await;
+                        info!("collector {name} stopped");
+                    }
+                    .instrument(span),
+                );
+            }
+            return Ok(set);
+        };
+
+        // Give each collector its own bounded channel, so a collector that
+        // outpaces the strategies only fills (and blocks on) its own buffer,
+        // while the others' events keep being interleaved.
+        let mut collector_receivers = Vec::new();
         for collector in self.collectors {
-            let event_sender = event_sender.clone();
-            set.spawn(async move {
-                info!("starting collector... ");
-                let mut event_stream = collector.get_event_stream().await.unwrap();
-                while let Some(event) = event_stream.next().await {
-                    match event_sender.send(event) {
-                        Ok(_) => {}
-                        Err(e) => error!("error sending event: {}", e),
+            let (collector_sender, collector_receiver) = mpsc::channel(collector_channel_capacity);
+            collector_receivers.push(ReceiverStream::new(collector_receiver));
+            let mut shutdown = self.shutdown.clone();
+            let name = collector.name().to_string();
+            let span = info_span!("collector", component = name.as_str());
+            set.spawn(
+                async move {
+                    info!("starting collector {name}... ");
+                    let mut event_stream = collector.get_event_stream().await.unwrap();
+                    while let Some(event) = tokio::select! {
+                        event = event_stream.next() => event,
+                        _ = shutdown_requested(&mut shutdown) => None,
+                    } {
+                        METRICS.record_collector_event(&name);
+                        let span = collected_event_span(&name);
+                        if collector_sender.send((event, span)).await.is_err() {
+                            info!("collector channel closed; stopping collector thread");
+                            break;
+                        }
                     }
+                    collector.close().await;
+                    info!("collector {name} stopped");
                 }
-            });
+                .instrument(span),
+            );
         }
 
+        // Interleave the collector channels into the event channel. Each event
+        // takes a permit that's only released once every strategy has
+        // processed it, so no more events are in flight than the event channel
+        // holds, and the rest queue per collector instead of being dropped.
+        let permits = Arc::new(Semaphore::new(self.event_channel_capacity));
+        set.spawn(async move {
+            let mut events = futures::stream::select_all(collector_receivers);
+            while let Some((event, span)) = events.next().await {
+                let Ok(permit) = permits.clone().acquire_owned().await else {
+                    break;
+                };
+                let event = BroadcastEvent {
+                    event,
+                    span,
+                    permit: Some(Arc::new(permit)),
+                };
+                match event_sender.send(event) {
+                    Ok(_) => {}
+                    Err(e) => error!("error sending event: {}", e),
+                }
+            }
+        });
+
         Ok(set)
     }
+
+    /// Run the engine until every collector is exhausted, e.g. once a replay
+    /// ends, or until `limit` has passed, whichever is first. At the limit
+    /// the engine is shut down as by [with_shutdown](Self::with_shutdown),
+    /// and any tasks still running after the drain deadline are aborted.
+    /// Returns the change in the process-wide [METRICS] over the run as a
+    /// summary, which also counts any other engine running in the process.
+    pub async fn run_for(
+        mut self,
+        limit: Duration,
+    ) -> Result<MetricsSnapshot, Box<dyn std::error::Error>> {
+        let (stop, shutdown) = watch::channel(false);
+        let mut external = self.shutdown.replace(shutdown);
+        let grace = self.drain_deadline;
+        let before = METRICS.snapshot();
+        let mut set = self.run().await?;
+
+        let deadline = tokio::time::sleep(limit);
+        tokio::pin!(deadline);
+        let stopped = loop {
+            tokio::select! {
+                joined = set.join_next() => match joined {
+                    Some(Err(e)) => error!("engine task failed: {}", e),
+                    Some(Ok(())) => {}
+                    None => break false,
+                },
+                _ = &mut deadline => {
+                    info!("run time limit of {:?} reached; shutting down", limit);
+                    break true;
+                }
+                _ = shutdown_requested(&mut external) => break true,
+            }
+        };
+
+        if stopped {
+            let _ = stop.send(true);
+            let finished =
+                tokio::time::timeout(grace, async { while set.join_next().await.is_some() {} })
+                    .await;
+            if finished.is_err() {
+                warn!("engine tasks still running after {:?}; aborting", grace);
+                set.shutdown().await;
+            }
+        }
+        Ok(METRICS.snapshot().since(&before))
+    }
+}
+
+/// Feed a synced strategy the events from `event_receiver` until the event
+/// channel closes or shutdown is requested, sending its actions to the
+/// executors.
+async fn run_strategy<E, A>(
+    mut strategy: Box<dyn Strategy<E, A>>,
+    mut event_receiver: Receiver<BroadcastEvent<E>>,
+    action_sender: Sender<TracedAction<A>>,
+    mut shutdown: Option<watch::Receiver<bool>>,
+    drain_deadline: Option<Duration>,
+    priority: Option<ActionPriority<A>>,
+) where
+    E: Clone,
+{
+    let name = strategy.name().to_string();
+    info!("starting strategy {name}... ");
+    loop {
+        let event = tokio::select! {
+            event = event_receiver.recv() => event,
+            _ = shutdown_requested(&mut shutdown) => {
+                if let Some(deadline) = drain_deadline {
+                    let drain = drain_events(
+                        strategy.as_mut(),
+                        &mut event_receiver,
+                        &action_sender,
+                        priority.as_ref(),
+                    );
+                    if tokio::time::timeout(deadline, drain).await.is_err() {
+                        warn!(
+                            "strategy {name} did not drain events within {:?}",
+                            deadline
+                        );
+                    }
+                }
+                info!("shutdown requested; stopping strategy {name}");
+                break;
+            }
+        };
+        match event {
+            Ok(BroadcastEvent {
+                event,
+                span,
+                permit,
+            }) => {
+                process_and_send(
+                    strategy.as_mut(),
+                    event,
+                    &span,
+                    &action_sender,
+                    priority.as_ref(),
+                )
+                .await;
+                drop(permit);
+            }
+            Err(RecvError::Closed) => {
+                info!("event channel closed; stopping strategy {name}");
+                break;
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                error!("strategy {name} lagged and skipped {} events", skipped);
+                METRICS.record_events_dropped(skipped);
+            }
+        }
+    }
+}
+
+/// Resolve once shutdown has been requested. Never resolves if there's no
+/// shutdown signal, or its sender is gone.
+async fn shutdown_requested(shutdown: &mut Option<watch::Receiver<bool>>) {
+    if let Some(shutdown) = shutdown
+        && shutdown.wait_for(|stop| *stop).await.is_ok()
+    {
+        return;
+    }
+    std::future::pending().await
+}
+
+/// Process an event, and send the resulting actions to the executors, highest
+/// priority first. Processing and execution are traced under a `strategy` span
+/// within the `event` span opened when the event was collected.
+async fn process_and_send<E, A>(
+    strategy: &mut dyn Strategy<E, A>,
+    event: E,
+    event_span: &Span,
+    action_sender: &Sender<TracedAction<A>>,
+    priority: Option<&ActionPriority<A>>,
+) {
+    let span = info_span!(parent: event_span, "strategy", component = strategy.name());
+    let started = Instant::now();
+    let mut actions = strategy.process_event(event).instrument(span.clone()).await;
+    METRICS.record_event_processed();
+    span.in_scope(|| {
+        debug!(elapsed = ?started.elapsed(), actions = actions.len(), "processed event");
+    });
+    if let Some(priority) = priority {
+        actions.sort_by_key(|action| std::cmp::Reverse(priority(action)));
+    }
+    for action in actions {
+        if let Err(e) = action_sender.send((action, span.clone())) {
+            error!("error sending action: {}", e);
+            break;
+        }
+    }
+}
+
+/// Process the events already buffered for a strategy.
+async fn drain_events<E: Clone, A>(
+    strategy: &mut dyn Strategy<E, A>,
+    event_receiver: &mut Receiver<BroadcastEvent<E>>,
+    action_sender: &Sender<TracedAction<A>>,
+    priority: Option<&ActionPriority<A>>,
+) {
+    loop {
+        match event_receiver.try_recv() {
+            Ok(BroadcastEvent {
+                event,
+                span,
+                permit,
+            }) => {
+                process_and_send(strategy, event, &span, action_sender, priority).await;
+                drop(permit);
+            }
+            Err(TryRecvError::Lagged(skipped)) => {
+                error!("strategy lagged and skipped {} events", skipped);
+                METRICS.record_events_dropped(skipped);
+            }
+            Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+        }
+    }
+}
+
+/// Execute an action, failing with [ExecutorError::Transient] if it takes
+/// longer than `timeout`, so the action is reported and dead-lettered like
+/// any other failure.
+async fn execute_with_timeout<A>(
+    executor: &dyn Executor<A>,
+    action: A,
+    timeout: Option<Duration>,
+) -> anyhow::Result<()> {
+    let Some(timeout) = timeout else {
+        return executor.execute(action).await;
+    };
+    match tokio::time::timeout(timeout, executor.execute(action)).await {
+        Ok(result) => result,
+        Err(_) => Err(ExecutorError::Transient(format!(
+            "executor {} timed out after {:?}",
+            executor.name(),
+            timeout
+        ))
+        .into()),
+    }
+}
+
+/// Hand a failed action to the dead letter channel, dropping it if the
+/// channel is full or closed.
+fn send_to_dead_letters<A>(dead_letters: &mpsc::Sender<FailedAction<A>>, failed: FailedAction<A>) {
+    if let Err(e) = dead_letters.try_send(failed) {
+        warn!("dropping failed action: {}", e);
+    }
+}
+
+/// Sync the state of a strategy, retrying with exponential backoff if it fails.
+async fn sync_state_with_retry<E, A>(
+    strategy: &mut dyn Strategy<E, A>,
+    retries: usize,
+    backoff: Duration,
+) -> anyhow::Result<()> {
+    let mut delay = backoff;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match strategy.sync_state().await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt <= retries => {
+                warn!(
+                    "strategy {} sync failed (attempt {}/{}): {}; retrying in {:?}",
+                    strategy.name(),
+                    attempt,
+                    retries + 1,
+                    e,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) => {
+                error!(
+                    "strategy {} sync failed after {} attempts: {}",
+                    strategy.name(),
+                    attempt,
+                    e
+                );
+                return Err(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{CountingExecutor, EchoStrategy, ScriptedCollector};
+    use async_trait::async_trait;
+
+    /// A strategy whose sync fails a fixed number of times before succeeding.
+    struct FlakyStrategy {
+        failures: usize,
+    }
+
+    #[async_trait]
+    impl Strategy<(), ()> for FlakyStrategy {
+        async fn sync_state(&mut self) -> anyhow::Result<()> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                anyhow::bail!("sync failed");
+            }
+            Ok(())
+        }
+
+        async fn process_event(&mut self, _event: ()) -> Vec<()> {
+            vec![]
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_state_retries_until_success() {
+        let mut strategy = FlakyStrategy { failures: 2 };
+        sync_state_with_retry(&mut strategy, 2, Duration::ZERO)
+            .await
+            .expect("sync should succeed on the last retry");
+
+        let mut strategy = FlakyStrategy { failures: 3 };
+        assert!(
+            sync_state_with_retry(&mut strategy, 2, Duration::ZERO)
+                .await
+                .is_err()
+        );
+    }
+
+    /// An executor that never finishes.
+    struct HangingExecutor;
+
+    #[async_trait]
+    impl Executor<()> for HangingExecutor {
+        async fn execute(&self, _action: ()) -> anyhow::Result<()> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn drain_processes_buffered_events() {
+        let (event_sender, mut event_receiver) = broadcast::channel(8);
+        let (action_sender, mut action_receiver) = broadcast::channel(8);
+        for event in 0..3u64 {
+            event_sender
+                .send(BroadcastEvent {
+                    event,
+                    span: Span::none(),
+                    permit: None,
+                })
+                .unwrap();
+        }
+
+        drain_events(&mut EchoStrategy, &mut event_receiver, &action_sender, None).await;
+        for event in 0..3u64 {
+            assert_eq!(action_receiver.try_recv().unwrap().0, event);
+        }
+        assert!(action_receiver.try_recv().is_err());
+    }
+
+    /// A strategy that returns the event's digits as actions.
+    struct DigitsStrategy;
+
+    #[async_trait]
+    impl Strategy<u64, u64> for DigitsStrategy {
+        async fn sync_state(&mut self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn process_event(&mut self, event: u64) -> Vec<u64> {
+            event
+                .to_string()
+                .bytes()
+                .map(|digit| (digit - b'0') as u64)
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn actions_are_sent_by_priority() {
+        let (action_sender, mut action_receiver) = broadcast::channel(8);
+        let priority: ActionPriority<u64> = Arc::new(|action| (*action % 2) as u8);
+
+        process_and_send(
+            &mut DigitsStrategy,
+            1234,
+            &Span::none(),
+            &action_sender,
+            Some(&priority),
+        )
+        .await;
+        let actions: Vec<u64> = (0..4)
+            .map(|_| action_receiver.try_recv().unwrap().0)
+            .collect();
+        assert_eq!(actions, vec![1, 3, 2, 4]);
+    }
+
+    #[tokio::test]
+    async fn run_for_stops_after_replay_or_limit() {
+        let executed = CountingExecutor::new();
+        let mut engine = Engine::<u64, u64>::new();
+        engine.add_collector(Box::new(ScriptedCollector::new(vec![1u64, 2, 3])));
+        engine.add_strategy(Box::new(EchoStrategy));
+        engine.add_executor(Box::new(executed.clone()));
+        engine.run_for(Duration::from_secs(60)).await.unwrap();
+        assert_eq!(executed.calls(), 3);
+
+        let mut engine =
+            Engine::<u64, u64>::new().with_drain_on_shutdown(Duration::from_millis(10));
+        engine.add_collector(Box::new(
+            ScriptedCollector::new(vec![1u64]).with_interval(Duration::from_secs(60)),
+        ));
+        engine.add_strategy(Box::new(EchoStrategy));
+        let run = engine.run_for(Duration::from_millis(10));
+        assert!(
+            tokio::time::timeout(Duration::from_secs(5), run)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_actions_go_to_dead_letters() {
+        let (dead_letters, mut failed) = mpsc::channel(8);
+        let mut engine = Engine::<u64, u64>::new().with_dead_letters(dead_letters);
+        engine.add_collector(Box::new(ScriptedCollector::new(vec![7u64])));
+        engine.add_strategy(Box::new(EchoStrategy));
+        engine.add_executor(Box::new(CountingExecutor::new().failing()));
+        engine.run_for(Duration::from_secs(60)).await.unwrap();
+
+        let action = failed.try_recv().unwrap();
+        assert_eq!(action.action, 7);
+        assert_eq!(action.error.to_string(), "rejected");
+        assert!(failed.try_recv().is_err());
+    }
+
+    /// A strategy that records the events it sees.
+    struct RecordingStrategy(Arc<std::sync::Mutex<Vec<u64>>>);
+
+    #[async_trait]
+    impl Strategy<u64, u64> for RecordingStrategy {
+        async fn sync_state(&mut self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn process_event(&mut self, event: u64) -> Vec<u64> {
+            self.0.lock().unwrap().push(event);
+            tokio::task::yield_now().await;
+            vec![]
+        }
+    }
+
+    #[tokio::test]
+    async fn noisy_collector_does_not_delay_quiet_one() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut engine = Engine::new()
+            .with_event_channel_capacity(4)
+            .with_collector_channel_capacity(2);
+        engine.add_collector(Box::new(ScriptedCollector::new(vec![0u64; 1000])));
+        engine.add_collector(Box::new(ScriptedCollector::new(vec![1u64])));
+        engine.add_strategy(Box::new(RecordingStrategy(seen.clone())));
+        engine.run_for(Duration::from_secs(60)).await.unwrap();
+
+        // Nothing is dropped, and the quiet event isn't queued behind the
+        // noisy collector's backlog.
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1001);
+        assert!(seen.iter().position(|&event| event == 1).unwrap() < 16);
+    }
+
+    #[tokio::test]
+    async fn every_strategy_sees_every_event() {
+        let executed = CountingExecutor::new();
+        let mut engine = Engine::<u64, u64>::new().with_collector_channel_capacity(1);
+        engine.add_collector(Box::new(ScriptedCollector::new(vec![1u64, 2, 3])));
+        engine.add_strategy(Box::new(EchoStrategy));
+        engine.add_strategy(Box::new(EchoStrategy));
+        engine.add_executor(Box::new(executed.clone()));
+        engine.run_for(Duration::from_secs(60)).await.unwrap();
+        assert_eq!(executed.calls(), 6);
+    }
+
+    #[tokio::test]
+    async fn strategies_can_be_added_while_running() {
+        let executed = CountingExecutor::new();
+        let mut engine = Engine::<u64, u64>::new();
+        engine.add_collector(Box::new(
+            ScriptedCollector::new(vec![1u64, 2, 3]).with_interval(Duration::from_millis(50)),
+        ));
+        engine.add_executor(Box::new(executed.clone()));
+        let handle = engine.strategy_handle();
+        let mut set = engine.run().await.unwrap();
+
+        handle.add_strategy(Box::new(EchoStrategy)).unwrap();
+        drop(handle);
+        let finished = tokio::time::timeout(Duration::from_secs(5), async {
+            while set.join_next().await.is_some() {}
+        })
+        .await;
+        assert!(finished.is_ok());
+        assert_eq!(executed.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn executor_timeout_fails_hung_action() {
+        let result =
+            execute_with_timeout(&HangingExecutor, (), Some(Duration::from_millis(10))).await;
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutorError>(),
+            Some(ExecutorError::Transient(_))
+        ));
+    }
 }
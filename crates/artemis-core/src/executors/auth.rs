@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+
+use alloy::hex;
+use alloy::primitives::keccak256;
+use alloy::providers::Provider;
+use alloy::rpc::json_rpc::{RpcRecv, RpcSend};
+use alloy::signers::Signer;
+use alloy::sol;
+use alloy::sol_types::{SolStruct, eip712_domain};
+use alloy::transports::{TransportErrorKind, TransportResult};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// The header relays read request signatures from.
+const FLASHBOTS_SIGNATURE_HEADER: &str = "x-flashbots-signature";
+
+sol! {
+    /// EIP-712 payload signed by the [Eip712](FlashbotsAuthScheme::Eip712) scheme.
+    struct FlashbotsRequest {
+        bytes32 bodyHash;
+    }
+}
+
+/// How requests to a Flashbots-style relay are signed. Both schemes send
+/// `address:signature` in the `X-Flashbots-Signature` header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlashbotsAuthScheme {
+    /// Sign the keccak hash of the request body as an EIP-191 message.
+    #[default]
+    Header,
+    /// Sign the keccak hash of the request body as EIP-712 typed data, under
+    /// the `Flashbots` version `1` domain.
+    Eip712,
+}
+
+/// Send a JSON-RPC request to a relay, signed with the given scheme.
+pub(crate) async fn send_with_auth<P, S, Params, Resp>(
+    provider: &P,
+    method: impl Into<Cow<'static, str>>,
+    params: Params,
+    signer: &S,
+    scheme: FlashbotsAuthScheme,
+) -> TransportResult<Resp>
+where
+    P: Provider,
+    S: Signer + Send + Sync,
+    Params: RpcSend,
+    Resp: RpcRecv,
+{
+    let call = provider.client().request(method, params);
+    let body = serde_json::to_vec(call.request()).map_err(TransportErrorKind::custom)?;
+    let body_hash = keccak256(&body);
+
+    let signature = match scheme {
+        FlashbotsAuthScheme::Header => {
+            signer
+                .sign_message(format!("{body_hash:?}").as_bytes())
+                .await
+        }
+        FlashbotsAuthScheme::Eip712 => {
+            let domain = eip712_domain! {
+                name: "Flashbots",
+                version: "1",
+            };
+            let payload = FlashbotsRequest {
+                bodyHash: body_hash,
+            };
+            signer
+                .sign_hash(&payload.eip712_signing_hash(&domain))
+                .await
+        }
+    }
+    .map_err(TransportErrorKind::custom)?;
+
+    let value = format!(
+        "{}:{}",
+        signer.address(),
+        hex::encode_prefixed(signature.as_bytes())
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        HeaderName::from_static(FLASHBOTS_SIGNATURE_HEADER),
+        HeaderValue::from_str(&value).map_err(TransportErrorKind::custom)?,
+    );
+
+    call.map_meta(|mut meta| {
+        meta.extensions_mut().insert(headers);
+        meta
+    })
+    .await
+}
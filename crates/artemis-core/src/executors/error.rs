@@ -0,0 +1,185 @@
+use alloy::transports::{RpcError, TransportError, TransportErrorKind};
+use std::fmt;
+
+/// A classified executor failure. Executors return this wrapped in an
+/// [anyhow::Error], so callers can recover it with
+/// `err.downcast_ref::<ExecutorError>()` and decide whether to retry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutorError {
+    /// A temporary failure, such as rate limiting or an overloaded relay.
+    /// Retrying the same action may succeed.
+    Transient(String),
+    /// The action was rejected and retrying it won't help, e.g. a bundle or
+    /// transaction failed validation.
+    Permanent(String),
+    /// The request was rejected because of authentication, e.g. a bad
+    /// `X-Flashbots-Signature`.
+    Auth(String),
+    /// The endpoint could not be reached.
+    Network(String),
+}
+
+impl ExecutorError {
+    /// Whether retrying the failed action may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Transient(_) | Self::Network(_))
+    }
+}
+
+impl fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transient(msg) => write!(f, "transient executor error: {msg}"),
+            Self::Permanent(msg) => write!(f, "permanent executor error: {msg}"),
+            Self::Auth(msg) => write!(f, "executor auth error: {msg}"),
+            Self::Network(msg) => write!(f, "executor network error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ExecutorError {}
+
+/// Classifies the error by its [RelayError] where the endpoint responded, and
+/// by the transport failure otherwise.
+impl From<TransportError> for ExecutorError {
+    fn from(err: TransportError) -> Self {
+        let msg = err.to_string().replace('\n', "");
+        match (&err, RelayError::from(&err)) {
+            (
+                RpcError::Transport(TransportErrorKind::MissingBatchResponse(_))
+                | RpcError::NullResp,
+                _,
+            ) => Self::Transient(msg),
+            (RpcError::Transport(kind), _) if !matches!(kind, TransportErrorKind::HttpError(_)) => {
+                Self::Network(msg)
+            }
+            (_, RelayError::RateLimited) => Self::Transient(msg),
+            (_, RelayError::Auth) => Self::Auth(msg),
+            (RpcError::Transport(TransportErrorKind::HttpError(http)), _) if http.status >= 500 => {
+                Self::Transient(msg)
+            }
+            _ => Self::Permanent(msg),
+        }
+    }
+}
+
+/// Why a relay rejected a submission, for logging and alerting on specific
+/// failure classes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayError {
+    /// Too many requests were sent.
+    RateLimited,
+    /// The bundle was malformed or failed validation.
+    InvalidBundle,
+    /// The request signature was missing or invalid.
+    Auth,
+    /// The bundle reverted when the relay simulated it.
+    SimulationReverted,
+    /// Anything else, with the raw error message.
+    Other(String),
+}
+
+impl RelayError {
+    /// A short name for the failure class, for structured log fields.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::InvalidBundle => "invalid_bundle",
+            Self::Auth => "auth",
+            Self::SimulationReverted => "simulation_reverted",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
+impl From<&TransportError> for RelayError {
+    fn from(err: &TransportError) -> Self {
+        let status = match err {
+            RpcError::Transport(TransportErrorKind::HttpError(http)) => Some(http.status),
+            _ => None,
+        };
+        let msg = match err {
+            RpcError::ErrorResp(payload) => payload.message.to_lowercase(),
+            _ => err.to_string().to_lowercase(),
+        };
+        if status == Some(429) || msg.contains("rate limit") || msg.contains("too many requests") {
+            Self::RateLimited
+        } else if matches!(status, Some(401 | 403))
+            || msg.contains("signature")
+            || msg.contains("unauthorized")
+        {
+            Self::Auth
+        } else if msg.contains("revert") || msg.contains("simulation") {
+            Self::SimulationReverted
+        } else if msg.contains("invalid") || msg.contains("malformed") {
+            Self::InvalidBundle
+        } else {
+            Self::Other(err.to_string().replace('\n', ""))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::transports::HttpError;
+
+    fn http_error(status: u16) -> TransportError {
+        RpcError::Transport(TransportErrorKind::HttpError(HttpError {
+            status,
+            body: String::new(),
+        }))
+    }
+
+    #[test]
+    fn classifies_http_errors() {
+        let err = ExecutorError::from(http_error(401));
+        assert!(matches!(err, ExecutorError::Auth(_)));
+        let err = ExecutorError::from(http_error(429));
+        assert!(matches!(err, ExecutorError::Transient(_)));
+        let err = ExecutorError::from(http_error(400));
+        assert!(matches!(err, ExecutorError::Permanent(_)));
+        assert!(ExecutorError::from(http_error(503)).is_retryable());
+        assert!(ExecutorError::from(TransportErrorKind::backend_gone()).is_retryable());
+    }
+
+    #[test]
+    fn classifies_relay_errors() {
+        let resp = |message: &str| -> TransportError {
+            RpcError::ErrorResp(alloy::rpc::json_rpc::ErrorPayload {
+                code: -32000,
+                message: message.to_string().into(),
+                data: None,
+            })
+        };
+        assert!(matches!(
+            ExecutorError::from(resp("rate limit exceeded")),
+            ExecutorError::Transient(_)
+        ));
+        assert!(matches!(
+            ExecutorError::from(resp("invalid signature")),
+            ExecutorError::Auth(_)
+        ));
+        assert!(matches!(
+            ExecutorError::from(resp("bundle simulation reverted")),
+            ExecutorError::Permanent(_)
+        ));
+        assert_eq!(RelayError::from(&http_error(429)), RelayError::RateLimited);
+        assert_eq!(
+            RelayError::from(&resp("invalid bundle")),
+            RelayError::InvalidBundle
+        );
+        assert_eq!(
+            RelayError::from(&resp("bundle simulation reverted")),
+            RelayError::SimulationReverted
+        );
+        assert_eq!(
+            RelayError::from(&resp("invalid signature")),
+            RelayError::Auth
+        );
+        assert!(matches!(
+            RelayError::from(&resp("unknown")),
+            RelayError::Other(_)
+        ));
+    }
+}
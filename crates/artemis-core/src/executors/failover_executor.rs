@@ -0,0 +1,97 @@
+use crate::types::Executor;
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// An executor that wraps a primary and a secondary [Executor](Executor), and
+/// only executes an action with the secondary if the primary fails or, when a
+/// timeout is set, takes too long. For example, a private relay with the
+/// public mempool as a fallback.
+pub struct FailoverExecutor<A> {
+    primary: Box<dyn Executor<A>>,
+    secondary: Box<dyn Executor<A>>,
+    primary_timeout: Option<Duration>,
+}
+
+impl<A> FailoverExecutor<A> {
+    pub fn new(primary: Box<dyn Executor<A>>, secondary: Box<dyn Executor<A>>) -> Self {
+        Self {
+            primary,
+            secondary,
+            primary_timeout: None,
+        }
+    }
+
+    /// Fall back to the secondary if the primary hasn't finished within
+    /// `timeout`.
+    pub fn with_primary_timeout(mut self, timeout: Duration) -> Self {
+        self.primary_timeout = Some(timeout);
+        self
+    }
+}
+
+#[async_trait]
+impl<A> Executor<A> for FailoverExecutor<A>
+where
+    A: Clone + Send + Sync + 'static,
+{
+    async fn execute(&self, action: A) -> Result<()> {
+        let primary = self.primary.execute(action.clone());
+        let result = match self.primary_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, primary)
+                .await
+                .unwrap_or_else(|_| Err(anyhow!("timed out after {timeout:?}"))),
+            None => primary.await,
+        };
+        let primary_error = match result {
+            Ok(()) => {
+                info!("executed action with primary {}", self.primary.name());
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        warn!(
+            "primary {} failed: {primary_error:#}; falling back to {}",
+            self.primary.name(),
+            self.secondary.name()
+        );
+        self.secondary
+            .execute(action)
+            .await
+            .map_err(|e| e.context(format!("primary also failed: {primary_error:#}")))?;
+        info!("executed action with secondary {}", self.secondary.name());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CountingExecutor;
+
+    #[tokio::test]
+    async fn secondary_only_runs_when_primary_fails() {
+        let (primary, secondary) = (CountingExecutor::new(), CountingExecutor::new());
+
+        let failover =
+            FailoverExecutor::new(Box::new(primary.clone()), Box::new(secondary.clone()));
+        failover.execute(()).await.unwrap();
+        assert_eq!(secondary.calls(), 0);
+
+        let failover = FailoverExecutor::new(
+            Box::new(primary.clone().failing()),
+            Box::new(secondary.clone()),
+        );
+        failover.execute(()).await.unwrap();
+        assert_eq!(secondary.calls(), 1);
+
+        let failover = FailoverExecutor::new(
+            Box::new(primary.clone().failing()),
+            Box::new(secondary.clone().failing()),
+        );
+        assert!(failover.execute(()).await.is_err());
+        assert_eq!(primary.calls(), 3);
+    }
+}
@@ -5,8 +5,11 @@ use alloy::rpc::types::mev::EthSendBundle;
 use alloy::{providers::Provider, signers::Signer};
 use anyhow::Result;
 use async_trait::async_trait;
-use tracing::{error, info};
+use tracing::info;
 
+use crate::executors::auth::{FlashbotsAuthScheme, send_with_auth};
+use crate::executors::error::ExecutorError;
+use crate::metrics::METRICS;
 use crate::types::Executor;
 
 /// A Flashbots executor that sends transactions to the Flashbots relay using Alloy primitives.
@@ -15,6 +18,8 @@ pub struct FlashbotsExecutor<P, AuthSigner> {
     mev_provider: Arc<P>,
     /// Signer used to authenticate requests with `X-Flashbots-Signature`.
     auth_signer: AuthSigner,
+    /// Scheme used to sign requests, defaulting to the header signature.
+    auth_scheme: FlashbotsAuthScheme,
 }
 
 impl<P, AuthSigner> FlashbotsExecutor<P, AuthSigner>
@@ -26,8 +31,14 @@ where
         Self {
             mev_provider,
             auth_signer,
+            auth_scheme: FlashbotsAuthScheme::default(),
         }
     }
+
+    pub fn with_auth_scheme(mut self, auth_scheme: FlashbotsAuthScheme) -> Self {
+        self.auth_scheme = auth_scheme;
+        self
+    }
 }
 
 #[async_trait]
@@ -42,20 +53,36 @@ where
             return Ok(());
         }
 
-        match self
-            .mev_provider
-            .send_bundle(bundle)
-            .with_auth(self.auth_signer.clone())
-            .await
-        {
+        let result = match self.auth_scheme {
+            FlashbotsAuthScheme::Header => {
+                self.mev_provider
+                    .send_bundle(bundle)
+                    .with_auth(self.auth_signer.clone())
+                    .await
+            }
+            FlashbotsAuthScheme::Eip712 => {
+                send_with_auth(
+                    self.mev_provider.as_ref(),
+                    "eth_sendBundle",
+                    (bundle,),
+                    &self.auth_signer,
+                    FlashbotsAuthScheme::Eip712,
+                )
+                .await
+            }
+        };
+        match result {
             Ok(Some(response)) => {
                 info!("Relay response: {}", response.bundle_hash);
+                METRICS.record_bundle_submitted();
             }
             Ok(None) => {
                 info!("No relay response");
             }
             Err(e) => {
-                error!("Failed to send bundle: {}", e);
+                return Err(
+                    anyhow::Error::new(ExecutorError::from(e)).context("Failed to send bundle")
+                );
             }
         }
 
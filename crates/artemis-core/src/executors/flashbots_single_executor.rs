@@ -1,3 +1,5 @@
+use crate::executors::auth::{FlashbotsAuthScheme, send_with_auth};
+use crate::executors::error::ExecutorError;
 use crate::types::Executor;
 use alloy::providers::Provider;
 use alloy::providers::ext::MevApi;
@@ -5,11 +7,13 @@ use alloy::rpc::types::mev::EthSendPrivateTransaction;
 use alloy::signers::Signer;
 use async_trait::async_trait;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::info;
 
 pub struct FlashbotsSingleExecutor<P, AuthSigner> {
     mev_provider: Arc<P>,
     auth_signer: AuthSigner,
+    /// Scheme used to sign requests, defaulting to the header signature.
+    auth_scheme: FlashbotsAuthScheme,
 }
 
 impl<P, AuthSigner> FlashbotsSingleExecutor<P, AuthSigner>
@@ -21,8 +25,14 @@ where
         Self {
             mev_provider,
             auth_signer,
+            auth_scheme: FlashbotsAuthScheme::default(),
         }
     }
+
+    pub fn with_auth_scheme(mut self, auth_scheme: FlashbotsAuthScheme) -> Self {
+        self.auth_scheme = auth_scheme;
+        self
+    }
 }
 
 #[async_trait]
@@ -32,12 +42,25 @@ where
     AuthSigner: Signer + Clone + Send + Sync + 'static,
 {
     async fn execute(&self, tx: EthSendPrivateTransaction) -> anyhow::Result<()> {
-        match self
-            .mev_provider
-            .send_private_transaction(tx)
-            .with_auth(self.auth_signer.clone())
-            .await
-        {
+        let result = match self.auth_scheme {
+            FlashbotsAuthScheme::Header => {
+                self.mev_provider
+                    .send_private_transaction(tx)
+                    .with_auth(self.auth_signer.clone())
+                    .await
+            }
+            FlashbotsAuthScheme::Eip712 => {
+                send_with_auth(
+                    self.mev_provider.as_ref(),
+                    "eth_sendPrivateTransaction",
+                    (tx,),
+                    &self.auth_signer,
+                    FlashbotsAuthScheme::Eip712,
+                )
+                .await
+            }
+        };
+        match result {
             Ok(Some(response)) => {
                 info!("Private tx sent successfully: {}", response);
             }
@@ -45,10 +68,8 @@ where
                 info!("No private send response");
             }
             Err(err) => {
-                error!(
-                    "Failed to send private tx: {}",
-                    err.to_string().replace("\n", "")
-                )
+                return Err(anyhow::Error::new(ExecutorError::from(err))
+                    .context("Failed to send private tx"));
             }
         }
 
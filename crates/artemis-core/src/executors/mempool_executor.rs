@@ -1,17 +1,29 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::executors::error::ExecutorError;
+use crate::signer::SharedSigner;
 use crate::types::Executor;
+use alloy::eips::eip7702::Authorization;
 use alloy::network::TransactionBuilder;
-use alloy::primitives::U256;
+use alloy::primitives::{Address, U256};
 use alloy::providers::Provider;
 use alloy::rpc::types::TransactionRequest;
+use alloy::signers::Signer;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::warn;
 
 /// An executor that sends transactions to the mempool.
 pub struct MempoolExecutor<M> {
     client: Arc<M>,
+    /// Next nonce to use for each sender, cached across executions.
+    nonces: Mutex<HashMap<Address, u64>>,
+    /// Account and the contract it delegates its code to with EIP-7702, if
+    /// set.
+    delegation: Option<(SharedSigner, Address)>,
 }
 
 /// Information about the gas bid for a transaction.
@@ -32,10 +44,131 @@ pub struct SubmitTxToMempool {
 
 impl<M: Provider + Send + Sync + 'static> MempoolExecutor<M> {
     pub fn new(client: Arc<M>) -> Self {
-        Self { client }
+        Self {
+            client,
+            nonces: Mutex::new(HashMap::new()),
+            delegation: None,
+        }
+    }
+
+    /// Delegate `authority`'s code to `delegate` with EIP-7702, e.g. for a
+    /// smart account. Until the delegation is on chain, each transaction
+    /// carries an authorization signed by `authority`, making it a 7702
+    /// transaction, with its gas price bid as both max fee and priority fee.
+    /// If `authority` also sends the transaction, its nonce must be managed
+    /// by this executor or set on the transaction.
+    pub fn with_delegation(
+        mut self,
+        authority: impl Signer + Send + Sync + 'static,
+        delegate: Address,
+    ) -> Self {
+        self.delegation = Some((SharedSigner::new(authority), delegate));
+        self
+    }
+
+    /// Fetch the pending nonce of `sender` from the node.
+    async fn pending_nonce(&self, sender: Address) -> Result<u64> {
+        self.client
+            .get_transaction_count(sender)
+            .pending()
+            .await
+            .map_err(ExecutorError::from)
+            .context("Error getting pending nonce")
+    }
+
+    /// The configured delegation, unless it's already on chain.
+    async fn pending_delegation(&self) -> Result<Option<&(SharedSigner, Address)>> {
+        let Some(delegation) = &self.delegation else {
+            return Ok(None);
+        };
+        let (authority, delegate) = delegation;
+        let code = self
+            .client
+            .get_code_at(authority.address())
+            .await
+            .map_err(ExecutorError::from)
+            .context("Error getting authority code")?;
+        Ok((!is_delegated_to(&code, *delegate)).then_some(delegation))
+    }
+
+    /// Attach an authorization for `delegation`, if any, to `tx`.
+    async fn authorize(
+        &self,
+        mut tx: TransactionRequest,
+        delegation: Option<&(SharedSigner, Address)>,
+    ) -> Result<TransactionRequest> {
+        let Some((authority, delegate)) = delegation else {
+            return Ok(tx);
+        };
+        let address = authority.address();
+        // a sender's own authorization is applied after its transaction
+        // has used up the current nonce
+        let nonce = match tx.nonce {
+            Some(nonce) if tx.from == Some(address) => nonce + 1,
+            _ => self.pending_nonce(address).await?,
+        };
+        let chain_id = self
+            .client
+            .get_chain_id()
+            .await
+            .map_err(ExecutorError::from)
+            .context("Error getting chain id")?;
+        let authorization = Authorization {
+            chain_id: U256::from(chain_id),
+            address: *delegate,
+            nonce,
+        };
+        let signature = authority
+            .sign_hash(&authorization.signature_hash())
+            .await
+            .context("Error signing authorization")?;
+        tx.authorization_list = Some(vec![authorization.into_signed(signature)]);
+        if let Some(gas_price) = tx.gas_price.take() {
+            tx = tx
+                .with_max_fee_per_gas(gas_price)
+                .with_max_priority_fee_per_gas(gas_price);
+        }
+        Ok(tx)
+    }
+
+    /// Reserve `count` consecutive nonces of `sender`, returning the first.
+    /// The lock is only held for the reservation, so sends don't wait on each
+    /// other. With `resync`, the cached nonce is replaced by the node's.
+    async fn reserve_nonces(&self, sender: Address, count: u64, resync: bool) -> Result<u64> {
+        let mut nonces = self.nonces.lock().await;
+        let nonce = match nonces.get(&sender) {
+            Some(nonce) if !resync => *nonce,
+            _ => self.pending_nonce(sender).await?,
+        };
+        nonces.insert(sender, nonce + count);
+        Ok(nonce)
+    }
+
+    /// Give back nonces reserved for a send that failed. If later nonces
+    /// were reserved since, the cache is dropped instead, so that the next
+    /// send resyncs rather than leaving a gap.
+    async fn release_nonces(&self, sender: Address, nonce: u64, count: u64) {
+        let mut nonces = self.nonces.lock().await;
+        if nonces.get(&sender) == Some(&(nonce + count)) {
+            nonces.insert(sender, nonce);
+        } else {
+            nonces.remove(&sender);
+        }
     }
 }
 
+/// Whether `code` is an EIP-7702 delegation designator pointing at `delegate`.
+fn is_delegated_to(code: &[u8], delegate: Address) -> bool {
+    code.len() == 23 && code[..3] == [0xef, 0x01, 0x00] && code[3..] == delegate[..]
+}
+
+/// Whether a node error indicates the transaction nonce is out of sync, e.g.
+/// after a reorg.
+fn is_nonce_error(err: &impl std::fmt::Display) -> bool {
+    let msg = err.to_string().to_lowercase();
+    msg.contains("nonce too low") || msg.contains("nonce too high") || msg.contains("invalid nonce")
+}
+
 #[async_trait]
 impl<M> Executor<SubmitTxToMempool> for MempoolExecutor<M>
 where
@@ -48,6 +181,7 @@ where
             self.client
                 .estimate_gas(tx.clone())
                 .await
+                .map_err(ExecutorError::from)
                 .context("Error estimating gas usage")?,
         );
 
@@ -62,10 +196,91 @@ where
             self.client
                 .get_gas_price()
                 .await
+                .map_err(ExecutorError::from)
                 .context("Error getting gas price")?
         };
         tx = tx.with_gas_price(bid_gas_price);
-        let _pending = self.client.send_transaction(tx).await?;
+
+        let delegation = self.pending_delegation().await?;
+
+        // leave nonce management to the provider if we can't or shouldn't manage it
+        let Some(sender) = tx.from.filter(|_| tx.nonce.is_none()) else {
+            let tx = self.authorize(tx, delegation).await?;
+            let _pending = self
+                .client
+                .send_transaction(tx)
+                .await
+                .map_err(ExecutorError::from)?;
+            return Ok(());
+        };
+
+        // the sender's own authorization uses up another of its nonces
+        let own = delegation.is_some_and(|(authority, _)| authority.address() == sender);
+        let count = 1 + u64::from(own);
+        let mut nonce = self.reserve_nonces(sender, count, false).await?;
+        let mut sent = match self
+            .authorize(tx.clone().with_nonce(nonce), delegation)
+            .await
+        {
+            Ok(authorized) => self.client.send_transaction(authorized).await,
+            Err(err) => {
+                self.release_nonces(sender, nonce, count).await;
+                return Err(err);
+            }
+        };
+        if let Err(err) = &sent
+            && is_nonce_error(err)
+        {
+            // our cached nonce is stale, resync from the node and retry once
+            warn!(
+                "nonce {} rejected for {}, resyncing: {}",
+                nonce, sender, err
+            );
+            nonce = self.reserve_nonces(sender, count, true).await?;
+            sent = match self.authorize(tx.with_nonce(nonce), delegation).await {
+                Ok(authorized) => self.client.send_transaction(authorized).await,
+                Err(err) => {
+                    self.release_nonces(sender, nonce, count).await;
+                    return Err(err);
+                }
+            };
+        }
+        if let Err(err) = sent {
+            self.release_nonces(sender, nonce, count).await;
+            return Err(ExecutorError::from(err).into());
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::MockProvider;
+
+    #[tokio::test]
+    async fn releases_nonces_of_failed_sends() {
+        let executor = MempoolExecutor::new(Arc::new(MockProvider::new().with_nonce(5)));
+        let sender = Address::repeat_byte(1);
+        assert_eq!(executor.reserve_nonces(sender, 1, false).await.unwrap(), 5);
+        assert_eq!(executor.reserve_nonces(sender, 2, false).await.unwrap(), 6);
+
+        // the last reservation is given back
+        executor.release_nonces(sender, 6, 2).await;
+        assert_eq!(executor.reserve_nonces(sender, 1, false).await.unwrap(), 6);
+
+        // an earlier one would leave a gap, so the node is asked again
+        executor.release_nonces(sender, 5, 1).await;
+        assert_eq!(executor.reserve_nonces(sender, 1, false).await.unwrap(), 5);
+    }
+
+    #[test]
+    fn detects_delegation_designator() {
+        let delegate = Address::repeat_byte(0xaa);
+        let mut code = vec![0xef, 0x01, 0x00];
+        code.extend_from_slice(delegate.as_slice());
+        assert!(is_delegated_to(&code, delegate));
+        assert!(!is_delegated_to(&code, Address::repeat_byte(0xbb)));
+        assert!(!is_delegated_to(&[], delegate));
+    }
+}
@@ -1,16 +1,153 @@
+use crate::executors::auth::{FlashbotsAuthScheme, send_with_auth};
+use crate::executors::error::{ExecutorError, RelayError};
+use crate::metrics::METRICS;
 use crate::types::Executor;
+use alloy::primitives::{B256, keccak256};
 use alloy::providers::{Provider, ext::MevApi};
 use alloy::rpc::types::mev::MevSendBundle;
 use alloy::signers::Signer;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
-use std::sync::Arc;
-use tracing::{error, info};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{info, warn};
+
+/// Maximum number of recent submissions remembered for deduplication.
+const DEDUP_CAPACITY: usize = 1024;
+/// RPC method bundles are cancelled with by default.
+pub const DEFAULT_CANCEL_METHOD: &str = "eth_cancelBundle";
+
+/// Recently submitted bundles, keyed on (bundle body hash, target block).
+#[derive(Debug)]
+struct DedupCache {
+    window: Duration,
+    entries: VecDeque<((B256, u64), Instant)>,
+}
+
+impl DedupCache {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Remember `key`, returning whether it was already submitted within the
+    /// window.
+    fn check_and_insert(&mut self, key: (B256, u64)) -> bool {
+        while self
+            .entries
+            .front()
+            .is_some_and(|(_, at)| at.elapsed() > self.window)
+        {
+            self.entries.pop_front();
+        }
+        if self.entries.iter().any(|(entry, _)| *entry == key) {
+            return true;
+        }
+        if self.entries.len() == DEDUP_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, Instant::now()));
+        false
+    }
+
+    /// Forget `key`, so that it can be submitted again.
+    fn remove(&mut self, key: (B256, u64)) {
+        self.entries.retain(|(entry, _)| *entry != key);
+    }
+}
+
+/// Stops submissions after too many consecutive failures. Once open, the
+/// circuit stays open for a cooldown, then lets a single probe submission
+/// through: success closes it again, and failure reopens it.
+#[derive(Debug)]
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            consecutive_failures: 0,
+            opened_at: None,
+            probing: false,
+        }
+    }
+
+    /// Whether a submission may be attempted now.
+    fn allow(&mut self) -> bool {
+        let Some(opened_at) = self.opened_at else {
+            return true;
+        };
+        if self.probing || opened_at.elapsed() < self.cooldown {
+            return false;
+        }
+        self.probing = true;
+        true
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probing = false;
+    }
+
+    /// Record a failed submission, returning whether this opened the circuit,
+    /// or reopened it after a failed probe.
+    fn record_failure(&mut self) -> bool {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let was_open = self.opened_at.is_some() && !self.probing;
+        if self.probing || self.consecutive_failures >= self.threshold {
+            self.opened_at = Some(Instant::now());
+            self.probing = false;
+        }
+        !was_open && self.opened_at.is_some()
+    }
+}
+
+/// A request to cancel a previously submitted bundle, identified by the bundle
+/// hash the relay returned for it, as from
+/// [send_bundle_tracked](MevshareExecutor::send_bundle_tracked).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelBundle {
+    pub bundle_hash: B256,
+}
+
+/// A bundle the relay accepted, along with the bundle hash it assigned.
+#[derive(Debug, Clone)]
+pub struct SubmittedBundle {
+    pub bundle_hash: B256,
+    pub bundle: MevSendBundle,
+}
 
 /// An executor that sends bundles to the MEV-share matchmaker.
 pub struct MevshareExecutor<P, S> {
     mev_provider: Arc<P>,
     auth_signer: S,
+    /// Scheme used to sign requests, defaulting to the header signature.
+    auth_scheme: FlashbotsAuthScheme,
+    /// Limits the number of outstanding submissions, if set.
+    in_flight: Option<Arc<Semaphore>>,
+    /// Recently submitted bundles, if deduplication is enabled.
+    dedup: Option<Mutex<DedupCache>>,
+    /// Halts submissions during sustained failures, if enabled.
+    circuit_breaker: Option<Mutex<CircuitBreaker>>,
+    /// RPC method used to cancel bundles.
+    cancel_method: String,
+    /// Receives every bundle the relay accepted, if set.
+    submitted_sender: Option<UnboundedSender<SubmittedBundle>>,
 }
 
 impl<P, S> MevshareExecutor<P, S>
@@ -22,34 +159,242 @@ where
         Self {
             mev_provider,
             auth_signer,
+            auth_scheme: FlashbotsAuthScheme::default(),
+            in_flight: None,
+            dedup: None,
+            circuit_breaker: None,
+            cancel_method: DEFAULT_CANCEL_METHOD.to_string(),
+            submitted_sender: None,
         }
     }
-}
 
-#[async_trait]
-impl<P, S> Executor<MevSendBundle> for MevshareExecutor<P, S>
-where
-    P: Provider + Send + Sync + 'static,
-    S: Signer + Clone + Send + Sync + 'static,
-{
-    async fn execute(&self, bundle: MevSendBundle) -> Result<()> {
-        match self
-            .mev_provider
-            .send_mev_bundle(bundle.clone())
-            .with_auth(self.auth_signer.clone())
-            .await
-        {
+    pub fn with_auth_scheme(mut self, auth_scheme: FlashbotsAuthScheme) -> Self {
+        self.auth_scheme = auth_scheme;
+        self
+    }
+
+    /// Allow at most `max_in_flight` submissions to be outstanding at once.
+    /// Further submissions wait for one to finish.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.in_flight = Some(Arc::new(Semaphore::new(max_in_flight.max(1))));
+        self
+    }
+
+    /// Skip bundles whose body was already submitted for the same target
+    /// block within `window`, unless the submission failed with a
+    /// [retryable](ExecutorError::is_retryable) error.
+    pub fn with_dedup(mut self, window: Duration) -> Self {
+        self.dedup = Some(Mutex::new(DedupCache::new(window)));
+        self
+    }
+
+    /// Stop submitting for `cooldown` after `threshold` consecutive failures,
+    /// then probe with a single submission before resuming. Bundles the relay
+    /// rejects as [ExecutorError::Permanent] don't count as failures.
+    pub fn with_circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some(Mutex::new(CircuitBreaker::new(threshold, cooldown)));
+        self
+    }
+
+    /// Cancel bundles by calling `method` on the relay with the bundle hash,
+    /// instead of [DEFAULT_CANCEL_METHOD].
+    pub fn with_cancel_method(mut self, method: impl Into<String>) -> Self {
+        self.cancel_method = method.into();
+        self
+    }
+
+    /// Send every bundle the relay accepts to `sender`, along with its bundle
+    /// hash, so the strategy that produced it can later cancel it. Bundles are
+    /// dropped once its receiver is gone.
+    pub fn with_submitted_sender(mut self, sender: UnboundedSender<SubmittedBundle>) -> Self {
+        self.submitted_sender = Some(sender);
+        self
+    }
+
+    /// Send `bundle` to the matchmaker, returning the bundle hash the relay
+    /// assigned it, e.g. to poll its stats or cancel it. Returns `None` if the
+    /// relay sent no response, or the bundle was skipped as a duplicate.
+    /// Fails with [ExecutorError::Transient] while the circuit is open.
+    pub async fn send_bundle_tracked(&self, bundle: MevSendBundle) -> Result<Option<B256>> {
+        let dedup_key = match &self.dedup {
+            Some(dedup) => {
+                let key = Self::dedup_key(&bundle)?;
+                if dedup
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .check_and_insert(key)
+                {
+                    warn!("skipping duplicate bundle for block {}", key.1);
+                    return Ok(None);
+                }
+                Some(key)
+            }
+            None => None,
+        };
+        let allowed = self
+            .circuit_breaker
+            .as_ref()
+            .is_none_or(|breaker| breaker.lock().unwrap_or_else(|e| e.into_inner()).allow());
+        if !allowed {
+            if let (Some(dedup), Some(key)) = (&self.dedup, dedup_key) {
+                dedup.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+            }
+            return Err(ExecutorError::Transient(format!(
+                "circuit open, skipping bundle for block {}",
+                bundle.inclusion.block
+            ))
+            .into());
+        }
+        let _permit = match &self.in_flight {
+            Some(in_flight) => Some(in_flight.acquire().await?),
+            None => None,
+        };
+        let result = match self.auth_scheme {
+            FlashbotsAuthScheme::Header => {
+                self.mev_provider
+                    .send_mev_bundle(bundle.clone())
+                    .with_auth(self.auth_signer.clone())
+                    .await
+            }
+            FlashbotsAuthScheme::Eip712 => {
+                send_with_auth(
+                    self.mev_provider.as_ref(),
+                    "mev_sendBundle",
+                    (bundle.clone(),),
+                    &self.auth_signer,
+                    FlashbotsAuthScheme::Eip712,
+                )
+                .await
+            }
+        };
+        let result = result.map_err(|e| (RelayError::from(&e), ExecutorError::from(e)));
+        if let Some(breaker) = &self.circuit_breaker {
+            let mut breaker = breaker.lock().unwrap_or_else(|e| e.into_inner());
+            match &result {
+                // the relay is up, it only rejected this bundle
+                Ok(_) | Err((_, ExecutorError::Permanent(_))) => breaker.record_success(),
+                Err(_) => {
+                    if breaker.record_failure() {
+                        warn!(
+                            "MEV-share submissions failing, pausing for {:?}",
+                            breaker.cooldown
+                        );
+                    }
+                }
+            }
+        }
+        match result {
             Ok(Some(response)) => {
                 info!("MEV-share relay bundle response: {}", response.bundle_hash);
+                METRICS.record_bundle_submitted();
+                if let Some(sender) = &self.submitted_sender {
+                    // the receiver may go away at any time, dropping the bundle
+                    let _ = sender.send(SubmittedBundle {
+                        bundle_hash: response.bundle_hash,
+                        bundle,
+                    });
+                }
+                Ok(Some(response.bundle_hash))
             }
             Ok(None) => {
                 info!("MEV-share no bundle response");
+                Ok(None)
             }
-            Err(e) => {
-                error!("failed to send mev bundle: {}", e);
+            Err((relay_error, error)) => {
+                // let a retry of a submission that may yet succeed through
+                if error.is_retryable()
+                    && let (Some(dedup), Some(key)) = (&self.dedup, dedup_key)
+                {
+                    dedup.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+                }
+                Err(anyhow::Error::new(error).context(format!(
+                    "failed to send mev bundle for block {} ({})",
+                    bundle.inclusion.block,
+                    relay_error.kind()
+                )))
             }
         }
+    }
+
+    /// Key identifying a bundle for deduplication.
+    fn dedup_key(bundle: &MevSendBundle) -> Result<(B256, u64)> {
+        let body = serde_json::to_vec(&bundle.bundle_body)?;
+        Ok((keccak256(body), bundle.inclusion.block))
+    }
+}
+
+#[async_trait]
+impl<P, S> Executor<MevSendBundle> for MevshareExecutor<P, S>
+where
+    P: Provider + Send + Sync + 'static,
+    S: Signer + Clone + Send + Sync + 'static,
+{
+    async fn execute(&self, bundle: MevSendBundle) -> Result<()> {
+        self.send_bundle_tracked(bundle).await.map(|_| ())
+    }
+}
 
+#[async_trait]
+impl<P, S> Executor<CancelBundle> for MevshareExecutor<P, S>
+where
+    P: Provider + Send + Sync + 'static,
+    S: Signer + Clone + Send + Sync + 'static,
+{
+    async fn execute(&self, cancel: CancelBundle) -> Result<()> {
+        send_with_auth::<_, _, _, serde_json::Value>(
+            self.mev_provider.as_ref(),
+            self.cancel_method.clone(),
+            (cancel,),
+            &self.auth_signer,
+            self.auth_scheme,
+        )
+        .await
+        .map_err(|e| anyhow::Error::new(ExecutorError::from(e)))
+        .with_context(|| format!("failed to cancel bundle {}", cancel.bundle_hash))?;
+        info!("cancelled MEV-share bundle {}", cancel.bundle_hash);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_cache_suppresses_repeats_per_block() {
+        let mut cache = DedupCache::new(Duration::from_secs(60));
+        let hash = B256::repeat_byte(1);
+        assert!(!cache.check_and_insert((hash, 10)));
+        assert!(cache.check_and_insert((hash, 10)));
+        assert!(!cache.check_and_insert((hash, 11)));
+
+        cache.remove((hash, 10));
+        assert!(!cache.check_and_insert((hash, 10)));
+
+        let mut cache = DedupCache::new(Duration::ZERO);
+        assert!(!cache.check_and_insert((hash, 10)));
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(!cache.check_and_insert((hash, 10)));
+    }
+
+    #[test]
+    fn circuit_breaker_opens_and_probes() {
+        let mut breaker = CircuitBreaker::new(2, Duration::ZERO);
+        assert!(breaker.allow());
+        assert!(!breaker.record_failure());
+        assert!(breaker.record_failure());
+
+        // After the cooldown, only one probe is let through.
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        // A failed probe reopens the circuit straight away.
+        assert!(breaker.record_failure());
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert!(breaker.allow() && breaker.allow());
+
+        let mut breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(!breaker.allow());
+    }
+}
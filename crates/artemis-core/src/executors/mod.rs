@@ -2,6 +2,16 @@
 //! executing them in different domains. For example, an executor might take a
 //! `SubmitTx` action and submit it to the mempool.
 
+/// This module contains the [ExecutorError](error::ExecutorError) returned by executors,
+/// and the classification of relay errors.
+pub mod error;
+
+/// This module contains the signing schemes used to authenticate with relays.
+pub mod auth;
+
+/// This executor falls back to a secondary executor when the primary fails.
+pub mod failover_executor;
+
 /// This executor submits transactions to the flashbots relay.
 pub mod flashbots_executor;
 
@@ -13,3 +23,6 @@ pub mod mev_share_executor;
 
 /// This executor submits private fast transactions to flashbots.
 pub mod flashbots_single_executor;
+
+/// This executor submits raw signed transactions to the public mempool.
+pub mod raw_tx_executor;
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use crate::executors::error::ExecutorError;
+use crate::types::Executor;
+use alloy::primitives::Bytes;
+use alloy::providers::Provider;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tracing::info;
+
+/// An executor that submits already signed, raw transactions to the mempool
+/// via `eth_sendRawTransaction`.
+pub struct RawTxExecutor<M> {
+    client: Arc<M>,
+}
+
+impl<M: Provider + Send + Sync + 'static> RawTxExecutor<M> {
+    pub fn new(client: Arc<M>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl<M> Executor<Bytes> for RawTxExecutor<M>
+where
+    M: Provider + Send + Sync + 'static,
+{
+    /// Send a raw signed transaction to the mempool.
+    async fn execute(&self, raw_tx: Bytes) -> Result<()> {
+        let pending = self
+            .client
+            .send_raw_transaction(&raw_tx)
+            .await
+            .map_err(ExecutorError::from)
+            .context("Error sending raw transaction")?;
+        info!("Raw tx sent: {}", pending.tx_hash());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::MockProvider;
+    use alloy::primitives::B256;
+
+    #[tokio::test]
+    async fn sends_raw_tx_and_classifies_rejections() {
+        let provider = Arc::new(MockProvider::new());
+        let executor = RawTxExecutor::new(provider.clone());
+        let raw_tx = Bytes::from_static(&[0x02, 0xf8]);
+
+        provider.asserter().push_success(&B256::repeat_byte(1));
+        executor.execute(raw_tx.clone()).await.unwrap();
+
+        provider.asserter().push_failure_msg("nonce too low");
+        let err = executor.execute(raw_tx).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutorError>(),
+            Some(ExecutorError::Permanent(_))
+        ));
+    }
+}
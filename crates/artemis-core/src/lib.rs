@@ -25,5 +25,24 @@ pub mod collectors;
 pub mod engine;
 /// This module contains [executor](types::Executor) implementations.
 pub mod executors;
+/// This module contains a tracing filter setting log levels per component.
+#[cfg(feature = "logging")]
+pub mod logging;
+/// This module contains counters describing the activity of the engine.
+pub mod metrics;
+/// This module contains the MEV-Share event types emitted by the
+/// [MevShareCollector](collectors::mevshare_collector::MevShareCollector).
+pub mod mevshare;
+/// This module contains helpers for querying relays about submitted bundles.
+pub mod relay;
+/// This module contains a cloneable handle for using remote signers, such as
+/// KMS-backed ones, with executors and strategies.
+pub mod signer;
+/// This module contains a mock provider, scripted collector and fixture
+/// strategies and executors for tests.
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 /// This module contains the core type definitions for Artemis.
 pub mod types;
+/// This module contains helpers for setting up and calling providers.
+pub mod util;
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id};
+use tracing::subscriber::Interest;
+use tracing::{Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Filter, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, registry};
+
+/// Name of the span field the [Engine](crate::engine::Engine) tags each
+/// component's span with.
+pub const COMPONENT_FIELD: &str = "component";
+
+/// Log levels per engine component. Components are matched on their
+/// [name](crate::types::Strategy::name), either in full or by the type name
+/// without its module path and generics, e.g. `MevShareUniArb`. Logs from
+/// outside any component, or from components without a level, use the level
+/// of their target, or the default level if no target matches.
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    default: LevelFilter,
+    components: HashMap<String, LevelFilter>,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl LogConfig {
+    pub fn new(default: LevelFilter) -> Self {
+        Self {
+            default,
+            components: HashMap::new(),
+            targets: Vec::new(),
+        }
+    }
+
+    /// Log targets within `target`, e.g. a crate name, at `level`. The longest
+    /// matching target wins.
+    pub fn with_target(mut self, target: impl Into<String>, level: LevelFilter) -> Self {
+        self.targets.push((target.into(), level));
+        self
+    }
+
+    /// Log the component named `name` at `level`.
+    pub fn with_component(mut self, name: impl Into<String>, level: LevelFilter) -> Self {
+        self.components.insert(name.into(), level);
+        self
+    }
+
+    /// Install a global subscriber printing logs filtered by this config.
+    pub fn init(self) {
+        registry()
+            .with(tracing_subscriber::fmt::layer().with_filter(self))
+            .init();
+    }
+
+    /// The level for the component named `name`, if it has one.
+    fn component_level(&self, name: &str) -> Option<LevelFilter> {
+        let base = name.split('<').next().unwrap_or(name);
+        let short = base.rsplit("::").next().unwrap_or(base);
+        [name, base, short]
+            .into_iter()
+            .find_map(|key| self.components.get(key).copied())
+    }
+
+    /// The level for logs from `target` outside a component with a level.
+    fn target_level(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.components
+            .values()
+            .chain(self.targets.iter().map(|(_, level)| level))
+            .copied()
+            .fold(self.default, LevelFilter::max)
+    }
+}
+
+/// The level resolved for a component span, stored in its extensions.
+struct ComponentLevel(LevelFilter);
+
+/// Reads the component name off a span's fields.
+#[derive(Default)]
+struct ComponentVisitor(Option<String>);
+
+impl Visit for ComponentVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == COMPONENT_FIELD {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == COMPONENT_FIELD {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+impl<S> Filter<S> for LogConfig
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        // Spans are kept up to the highest level, so that they can tag the
+        // events within them.
+        if meta.is_span() {
+            return self.max_level() >= *meta.level();
+        }
+        let level = cx
+            .lookup_current()
+            .and_then(|span| {
+                span.scope()
+                    .find_map(|span| span.extensions().get::<ComponentLevel>().map(|c| c.0))
+            })
+            .unwrap_or_else(|| self.target_level(meta.target()));
+        level >= *meta.level()
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        if self.max_level() >= *meta.level() {
+            Interest::sometimes()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(self.max_level())
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = ComponentVisitor::default();
+        attrs.record(&mut visitor);
+        if let Some(level) = visitor.0.and_then(|name| self.component_level(&name))
+            && let Some(span) = ctx.span(id)
+        {
+            span.extensions_mut().insert(ComponentLevel(level));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tracing::{debug, info, info_span};
+
+    /// A layer that counts the events it sees.
+    struct CountingLayer(Arc<AtomicUsize>);
+
+    impl<S: Subscriber> Layer<S> for CountingLayer {
+        fn on_event(&self, _event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn filters_events_by_component() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let config =
+            LogConfig::new(LevelFilter::INFO).with_component("MyStrategy", LevelFilter::DEBUG);
+        let subscriber = registry().with(CountingLayer(count.clone()).with_filter(config));
+
+        tracing::subscriber::with_default(subscriber, || {
+            debug!("outside any component");
+            info_span!("collector", component = "my_crate::MyCollector")
+                .in_scope(|| debug!("from a collector"));
+            assert_eq!(count.load(Ordering::Relaxed), 0);
+
+            let strategy = info_span!("strategy", component = "my_crate::MyStrategy<u64>");
+            strategy.in_scope(|| {
+                debug!("from the strategy");
+                info_span!("event", id = 0).in_scope(|| debug!("within an event"));
+            });
+            assert_eq!(count.load(Ordering::Relaxed), 2);
+        });
+    }
+
+    #[test]
+    fn filters_events_by_target() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let config = LogConfig::new(LevelFilter::OFF)
+            .with_target("artemis_core", LevelFilter::INFO)
+            .with_target("artemis_core::logging", LevelFilter::DEBUG)
+            .with_component("MyStrategy", LevelFilter::TRACE);
+        let subscriber = registry().with(CountingLayer(count.clone()).with_filter(config));
+
+        tracing::subscriber::with_default(subscriber, || {
+            debug!("from this module");
+            debug!(target: "artemis_core", "from the crate");
+            info!(target: "artemis_core_ext", "from another crate");
+            info_span!("collector", component = "MyCollector")
+                .in_scope(|| info!(target: "alloy", "from a dependency"));
+            assert_eq!(count.load(Ordering::Relaxed), 1);
+
+            info_span!("strategy", component = "MyStrategy")
+                .in_scope(|| tracing::trace!(target: "alloy", "from within the strategy"));
+            assert_eq!(count.load(Ordering::Relaxed), 2);
+        });
+    }
+}
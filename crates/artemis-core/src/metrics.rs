@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Process-wide counters updated by the engine, collectors and executors.
+pub static METRICS: Metrics = Metrics::new();
+
+/// Counters describing the activity of an Artemis process.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    events_processed: AtomicU64,
+    actions_executed: AtomicU64,
+    reconnects: AtomicU64,
+    bundles_submitted: AtomicU64,
+    events_dropped: AtomicU64,
+    collector_events: Mutex<BTreeMap<String, u64>>,
+}
+
+/// A point-in-time copy of [Metrics].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Events processed by strategies.
+    pub events_processed: u64,
+    /// Actions executed successfully by executors.
+    pub actions_executed: u64,
+    /// Times a collector reconnected to its source.
+    pub reconnects: u64,
+    /// Bundles accepted by a relay.
+    pub bundles_submitted: u64,
+    /// Events strategies skipped because they fell too far behind.
+    pub events_dropped: u64,
+    /// Events emitted by each collector, keyed by [name](crate::types::Collector::name).
+    pub collector_events: BTreeMap<String, u64>,
+}
+
+impl Metrics {
+    pub const fn new() -> Self {
+        Self {
+            events_processed: AtomicU64::new(0),
+            actions_executed: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            bundles_submitted: AtomicU64::new(0),
+            events_dropped: AtomicU64::new(0),
+            collector_events: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn record_event_processed(&self) {
+        self.events_processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_action_executed(&self) {
+        self.actions_executed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_bundle_submitted(&self) {
+        self.bundles_submitted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_events_dropped(&self, count: u64) {
+        self.events_dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_collector_event(&self, collector: &str) {
+        let mut collector_events = self
+            .collector_events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match collector_events.get_mut(collector) {
+            Some(count) => *count += 1,
+            None => {
+                collector_events.insert(collector.to_string(), 1);
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            events_processed: self.events_processed.load(Ordering::Relaxed),
+            actions_executed: self.actions_executed.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            bundles_submitted: self.bundles_submitted.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+            collector_events: self
+                .collector_events
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
+}
+
+impl MetricsSnapshot {
+    /// The activity counted between `earlier` and this snapshot.
+    pub fn since(&self, earlier: &MetricsSnapshot) -> MetricsSnapshot {
+        MetricsSnapshot {
+            events_processed: self
+                .events_processed
+                .saturating_sub(earlier.events_processed),
+            actions_executed: self
+                .actions_executed
+                .saturating_sub(earlier.actions_executed),
+            reconnects: self.reconnects.saturating_sub(earlier.reconnects),
+            bundles_submitted: self
+                .bundles_submitted
+                .saturating_sub(earlier.bundles_submitted),
+            events_dropped: self.events_dropped.saturating_sub(earlier.events_dropped),
+            collector_events: self
+                .collector_events
+                .iter()
+                .filter_map(|(collector, &count)| {
+                    let earlier = earlier.collector_events.get(collector).copied();
+                    let count = count.saturating_sub(earlier.unwrap_or(0));
+                    (count > 0).then(|| (collector.clone(), count))
+                })
+                .collect(),
+        }
+    }
+
+    /// Render the snapshot in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let counters = [
+            (
+                "artemis_events_processed_total",
+                "Events processed by strategies.",
+                self.events_processed,
+            ),
+            (
+                "artemis_actions_executed_total",
+                "Actions executed successfully by executors.",
+                self.actions_executed,
+            ),
+            (
+                "artemis_reconnects_total",
+                "Times a collector reconnected to its source.",
+                self.reconnects,
+            ),
+            (
+                "artemis_bundles_submitted_total",
+                "Bundles accepted by a relay.",
+                self.bundles_submitted,
+            ),
+            (
+                "artemis_events_dropped_total",
+                "Events strategies skipped because they fell too far behind.",
+                self.events_dropped,
+            ),
+        ];
+        let mut text: String = counters
+            .iter()
+            .map(|(name, help, value)| {
+                format!("# HELP {name} {help}\n# TYPE {name} counter\n{name} {value}\n")
+            })
+            .collect();
+
+        let name = "artemis_collector_events_total";
+        text.push_str(&format!(
+            "# HELP {name} Events emitted by each collector.\n# TYPE {name} counter\n"
+        ));
+        for (collector, value) in &self.collector_events {
+            let collector = collector.replace('\\', "\\\\").replace('"', "\\\"");
+            text.push_str(&format!("{name}{{collector=\"{collector}\"}} {value}\n"));
+        }
+        text
+    }
+}
+
+/// Serve [METRICS] in the Prometheus text format over HTTP on `addr`, for
+/// every path. Runs until the listener fails.
+#[cfg(feature = "metrics")]
+pub async fn serve_metrics(addr: impl tokio::net::ToSocketAddrs) -> std::io::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            // The request itself doesn't matter, but read it so the client
+            // isn't reset before it sees the response.
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+
+            let body = METRICS.snapshot().to_prometheus();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            if let Err(e) = socket.write_all(response.as_bytes()).await {
+                tracing::warn!("failed to write metrics response: {e}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_prometheus_counters() {
+        let metrics = Metrics::new();
+        metrics.record_event_processed();
+        metrics.record_event_processed();
+        metrics.record_bundle_submitted();
+        metrics.record_events_dropped(3);
+        metrics.record_collector_event("blocks");
+        metrics.record_collector_event("blocks");
+
+        let text = metrics.snapshot().to_prometheus();
+        assert!(text.contains("# TYPE artemis_events_processed_total counter\n"));
+        assert!(text.contains("artemis_events_processed_total 2\n"));
+        assert!(text.contains("artemis_bundles_submitted_total 1\n"));
+        assert!(text.contains("artemis_reconnects_total 0\n"));
+        assert!(text.contains("artemis_events_dropped_total 3\n"));
+        assert!(text.contains("artemis_collector_events_total{collector=\"blocks\"} 2\n"));
+    }
+
+    #[test]
+    fn snapshot_since_counts_the_difference() {
+        let metrics = Metrics::new();
+        metrics.record_event_processed();
+        metrics.record_collector_event("blocks");
+        let before = metrics.snapshot();
+
+        metrics.record_event_processed();
+        metrics.record_collector_event("mempool");
+        let delta = metrics.snapshot().since(&before);
+        assert_eq!(delta.events_processed, 1);
+        assert_eq!(
+            delta.collector_events,
+            BTreeMap::from([("mempool".to_string(), 1)])
+        );
+    }
+}
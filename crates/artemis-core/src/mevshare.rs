@@ -0,0 +1,174 @@
+use alloy::primitives::{Address, B256, Bytes, Log, Selector, U256, keccak256};
+use alloy::rpc::types::mev::EthSendBundle;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+/// An event streamed by the MEV-Share SSE endpoint. This mirrors
+/// `alloy::rpc::types::mev::mevshare::Event`, but keeps every per-transaction
+/// field the relay may share.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// Hash of the transaction or bundle.
+    pub hash: B256,
+    /// Transactions in the bundle, or the single transaction of a tx event.
+    #[serde(rename = "txs", default, deserialize_with = "null_as_default")]
+    pub transactions: Vec<EventTransaction>,
+    /// Logs emitted by the transactions, if shared.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub logs: Vec<Log>,
+}
+
+impl Event {
+    /// A plain bundle backrunning this event with `tx` in `block`, for direct
+    /// submission to builders. Plain bundles can't reference a transaction by
+    /// hash, so the bundle holds the event's raw transaction `victim_tx`
+    /// ahead of `tx`. `None` if `victim_tx` isn't this event's transaction.
+    pub fn backrun_bundle(&self, victim_tx: Bytes, tx: Bytes, block: u64) -> Option<EthSendBundle> {
+        if keccak256(&victim_tx) != self.hash {
+            return None;
+        }
+        Some(EthSendBundle {
+            txs: vec![victim_tx, tx],
+            block_number: block,
+            ..Default::default()
+        })
+    }
+}
+
+/// A one-line summary of the event: its hash, the number of txs and logs, and
+/// the address of the first log, if any.
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} txs, {} logs",
+            self.hash,
+            self.transactions.len(),
+            self.logs.len()
+        )?;
+        if let Some(log) = self.logs.first() {
+            write!(f, ", first log {}", log.address)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A transaction within a MEV-Share [Event]. Every field is optional, and only
+/// populated if the sender chose to share it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTransaction {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<B256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_selector: Option<Selector>,
+    #[serde(rename = "callData", default, skip_serializing_if = "Option::is_none")]
+    pub calldata: Option<Bytes>,
+    /// Items of a nested bundle, when this entry describes a bundle rather
+    /// than a single transaction.
+    #[serde(
+        rename = "txs",
+        default,
+        deserialize_with = "null_as_default",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub transactions: Vec<EventTransaction>,
+}
+
+impl EventTransaction {
+    /// Whether this entry is a nested bundle.
+    pub fn is_bundle(&self) -> bool {
+        !self.transactions.is_empty()
+    }
+}
+
+/// The relay sends `null` instead of an empty list when nothing is shared.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_bundles_and_missing_fields() {
+        let event: Event = serde_json::from_str(
+            r#"{
+                "hash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "logs": null,
+                "txs": [
+                    { "to": "0x0000000000000000000000000000000000000002", "functionSelector": "0x12345678" },
+                    {
+                        "hash": "0x0000000000000000000000000000000000000000000000000000000000000003",
+                        "txs": [{ "value": "0x1", "callData": "0xabcd" }]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(event.logs.is_empty());
+        assert_eq!(event.transactions.len(), 2);
+        assert!(!event.transactions[0].is_bundle());
+        assert_eq!(event.transactions[0].value, None);
+
+        let nested = &event.transactions[1];
+        assert!(nested.is_bundle());
+        assert_eq!(nested.transactions[0].value, Some(U256::from(1)));
+        assert_eq!(
+            nested.transactions[0].calldata,
+            Some(Bytes::from_static(&[0xab, 0xcd]))
+        );
+    }
+
+    #[test]
+    fn displays_hash_and_counts() {
+        let mut event = Event {
+            hash: B256::with_last_byte(1),
+            transactions: vec![EventTransaction::default(); 2],
+            logs: vec![],
+        };
+        assert_eq!(event.to_string(), format!("{} (2 txs, 0 logs)", event.hash));
+
+        event.logs.push(Log::empty());
+        assert_eq!(
+            event.to_string(),
+            format!(
+                "{} (2 txs, 1 logs, first log {})",
+                event.hash,
+                Address::ZERO
+            )
+        );
+    }
+
+    #[test]
+    fn backrun_bundle_puts_victim_first() {
+        let victim_tx = Bytes::from_static(&[0x02, 0x00]);
+        let event = Event {
+            hash: keccak256(&victim_tx),
+            transactions: vec![],
+            logs: vec![],
+        };
+
+        let tx = Bytes::from_static(&[0x02, 0x01]);
+        let bundle = event
+            .backrun_bundle(victim_tx.clone(), tx.clone(), 10)
+            .unwrap();
+        assert_eq!(bundle.txs, vec![victim_tx, tx.clone()]);
+        assert_eq!(bundle.block_number, 10);
+
+        // Any other transaction isn't the event's.
+        assert!(event.backrun_bundle(tx.clone(), tx, 10).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::executors::auth::{FlashbotsAuthScheme, send_with_auth};
+use crate::executors::error::ExecutorError;
+use alloy::primitives::{B256, U64};
+use alloy::providers::Provider;
+use alloy::signers::Signer;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// When a builder saw a bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuilderTimestamp {
+    pub pubkey: String,
+    pub timestamp: String,
+}
+
+/// Status of a submitted bundle, as returned by `flashbots_getBundleStatsV2`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStats {
+    #[serde(default)]
+    pub is_simulated: bool,
+    #[serde(default)]
+    pub is_high_priority: bool,
+    #[serde(default)]
+    pub simulated_at: Option<String>,
+    #[serde(default)]
+    pub received_at: Option<String>,
+    /// Builders the relay forwarded the bundle to.
+    #[serde(default)]
+    pub considered_by_builders_at: Vec<BuilderTimestamp>,
+    /// Builders that included the bundle in a sealed block.
+    #[serde(default)]
+    pub sealed_by_builders_at: Vec<BuilderTimestamp>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleStatsRequest {
+    bundle_hash: B256,
+    block_number: U64,
+}
+
+/// Query the relay behind `provider` for the stats of the bundle with
+/// `bundle_hash` targeting `block`. The request is signed by `signer` the same
+/// way the executors sign submissions.
+pub async fn get_bundle_stats<P, S>(
+    provider: &P,
+    signer: &S,
+    bundle_hash: B256,
+    block: u64,
+) -> Result<BundleStats>
+where
+    P: Provider,
+    S: Signer + Send + Sync,
+{
+    get_bundle_stats_with_scheme(
+        provider,
+        signer,
+        FlashbotsAuthScheme::default(),
+        bundle_hash,
+        block,
+    )
+    .await
+}
+
+/// Like [get_bundle_stats], but signs the request with the given scheme.
+pub async fn get_bundle_stats_with_scheme<P, S>(
+    provider: &P,
+    signer: &S,
+    scheme: FlashbotsAuthScheme,
+    bundle_hash: B256,
+    block: u64,
+) -> Result<BundleStats>
+where
+    P: Provider,
+    S: Signer + Send + Sync,
+{
+    let request = BundleStatsRequest {
+        bundle_hash,
+        block_number: U64::from(block),
+    };
+    send_with_auth(
+        provider,
+        "flashbots_getBundleStatsV2",
+        (request,),
+        signer,
+        scheme,
+    )
+    .await
+    .map_err(|e| anyhow::Error::new(ExecutorError::from(e)))
+    .with_context(|| format!("failed to get stats for bundle {bundle_hash}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bundle_stats() {
+        let stats: BundleStats = serde_json::from_str(
+            r#"{
+                "isHighPriority": true,
+                "isSimulated": true,
+                "simulatedAt": "2022-10-06T21:36:06.317Z",
+                "receivedAt": "2022-10-06T21:36:06.250Z",
+                "consideredByBuildersAt": [
+                    { "pubkey": "0x81babee", "timestamp": "2022-10-06T21:36:06.343Z" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(stats.is_simulated);
+        assert!(stats.is_high_priority);
+        assert_eq!(stats.considered_by_builders_at.len(), 1);
+        assert!(stats.sealed_by_builders_at.is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+use alloy::primitives::{Address, B256, ChainId, Signature};
+use alloy::signers::{Result, Signer};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// A cheaply cloneable handle to any [Signer], so that signers that aren't
+/// local keys, such as alloy's AWS KMS or GCP KMS signers, can be used
+/// wherever a `Signer + Clone` is expected, e.g. as the auth signer of a
+/// [MevshareExecutor](crate::executors::mev_share_executor::MevshareExecutor).
+///
+/// The chain id is kept per handle, and [set_chain_id](Signer::set_chain_id)
+/// doesn't reach the wrapped signer. Transactions are signed by wallets
+/// rather than [Signer]s: an `EthereumWallet` can be built from a remote
+/// signer directly.
+#[derive(Clone)]
+pub struct SharedSigner {
+    inner: Arc<dyn Signer + Send + Sync>,
+    chain_id: Option<ChainId>,
+}
+
+impl SharedSigner {
+    pub fn new(signer: impl Signer + Send + Sync + 'static) -> Self {
+        let chain_id = signer.chain_id();
+        Self {
+            inner: Arc::new(signer),
+            chain_id,
+        }
+    }
+}
+
+impl std::fmt::Debug for SharedSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSigner")
+            .field("address", &self.inner.address())
+            .field("chain_id", &self.chain_id)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Signer for SharedSigner {
+    async fn sign_hash(&self, hash: &B256) -> Result<Signature> {
+        self.inner.sign_hash(hash).await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        self.inner.sign_message(message).await
+    }
+
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[tokio::test]
+    async fn signs_like_the_wrapped_signer() {
+        let local = PrivateKeySigner::random();
+        let shared = SharedSigner::new(local.clone());
+        assert_eq!(shared.address(), local.address());
+        assert_eq!(
+            shared.sign_message(b"bundle").await.unwrap(),
+            local.sign_message(b"bundle").await.unwrap()
+        );
+    }
+}
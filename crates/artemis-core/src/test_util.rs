@@ -0,0 +1,239 @@
+use crate::types::{Collector, CollectorStream, Executor, Strategy};
+use alloy::primitives::{Address, BlockNumber, U64, U128};
+use alloy::providers::{Provider, RootProvider, RpcWithBlock};
+use alloy::rpc::client::{NoParams, ProviderCall, RpcClient};
+use alloy::transport::mock::Asserter;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// A [Provider] that answers `get_gas_price`, `get_block_number`,
+/// `get_chain_id` and `get_transaction_count` with canned values, without
+/// touching the network. Every other request is answered from the responses
+/// queued on its [Asserter], in order.
+///
+/// Subscriptions aren't supported, so drive strategies and engines under test
+/// with a [ScriptedCollector] instead.
+#[derive(Debug, Clone)]
+pub struct MockProvider {
+    root: RootProvider,
+    asserter: Asserter,
+    gas_price: Arc<AtomicU64>,
+    block_number: Arc<AtomicU64>,
+    chain_id: Arc<AtomicU64>,
+    nonce: Arc<AtomicU64>,
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockProvider {
+    /// A mainnet provider at block 0, with a zero gas price and nonce.
+    pub fn new() -> Self {
+        let asserter = Asserter::new();
+        Self {
+            root: RootProvider::new(RpcClient::mocked(asserter.clone())),
+            asserter,
+            gas_price: Default::default(),
+            block_number: Default::default(),
+            chain_id: Arc::new(AtomicU64::new(1)),
+            nonce: Default::default(),
+        }
+    }
+
+    pub fn with_gas_price(self, gas_price: u64) -> Self {
+        self.set_gas_price(gas_price);
+        self
+    }
+
+    pub fn with_block_number(self, block_number: u64) -> Self {
+        self.set_block_number(block_number);
+        self
+    }
+
+    pub fn with_chain_id(self, chain_id: u64) -> Self {
+        self.chain_id.store(chain_id, Ordering::Relaxed);
+        self
+    }
+
+    pub fn with_nonce(self, nonce: u64) -> Self {
+        self.set_nonce(nonce);
+        self
+    }
+
+    pub fn set_gas_price(&self, gas_price: u64) {
+        self.gas_price.store(gas_price, Ordering::Relaxed);
+    }
+
+    pub fn set_block_number(&self, block_number: u64) {
+        self.block_number.store(block_number, Ordering::Relaxed);
+    }
+
+    pub fn set_nonce(&self, nonce: u64) {
+        self.nonce.store(nonce, Ordering::Relaxed);
+    }
+
+    /// The queue of responses for requests without a canned value.
+    pub fn asserter(&self) -> &Asserter {
+        &self.asserter
+    }
+}
+
+impl Provider for MockProvider {
+    fn root(&self) -> &RootProvider {
+        &self.root
+    }
+
+    fn get_gas_price(&self) -> ProviderCall<NoParams, U128, u128> {
+        ProviderCall::ready(Ok(self.gas_price.load(Ordering::Relaxed) as u128))
+    }
+
+    fn get_block_number(&self) -> ProviderCall<NoParams, U64, BlockNumber> {
+        ProviderCall::ready(Ok(self.block_number.load(Ordering::Relaxed)))
+    }
+
+    fn get_chain_id(&self) -> ProviderCall<NoParams, U64, u64> {
+        ProviderCall::ready(Ok(self.chain_id.load(Ordering::Relaxed)))
+    }
+
+    fn get_transaction_count(
+        &self,
+        _address: Address,
+    ) -> RpcWithBlock<Address, U64, u64, fn(U64) -> u64> {
+        let nonce = self.nonce.load(Ordering::Relaxed);
+        RpcWithBlock::new_provider(move |_block| ProviderCall::ready(Ok(nonce)))
+    }
+}
+
+/// A collector that emits a scripted sequence of events, optionally spaced
+/// out by a fixed interval, and then ends.
+pub struct ScriptedCollector<E> {
+    events: Vec<E>,
+    interval: Option<Duration>,
+}
+
+impl<E> ScriptedCollector<E> {
+    pub fn new(events: Vec<E>) -> Self {
+        Self {
+            events,
+            interval: None,
+        }
+    }
+
+    /// Wait `interval` before emitting each event.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+}
+
+#[async_trait]
+impl<E> Collector<E> for ScriptedCollector<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, E>> {
+        let events = futures::stream::iter(self.events.clone());
+        let Some(interval) = self.interval else {
+            return Ok(Box::pin(events));
+        };
+        Ok(Box::pin(events.then(move |event| async move {
+            tokio::time::sleep(interval).await;
+            event
+        })))
+    }
+}
+
+/// A strategy that echoes every event as an action.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EchoStrategy;
+
+#[async_trait]
+impl<E> Strategy<E, E> for EchoStrategy
+where
+    E: Send + 'static,
+{
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, event: E) -> Vec<E> {
+        vec![event]
+    }
+}
+
+/// An executor that counts the actions it's asked to execute. Clones share
+/// the count.
+#[derive(Debug, Clone, Default)]
+pub struct CountingExecutor {
+    calls: Arc<AtomicUsize>,
+    fail: bool,
+}
+
+impl CountingExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject every action, after counting it.
+    pub fn failing(mut self) -> Self {
+        self.fail = true;
+        self
+    }
+
+    /// The number of actions executed so far, including rejected ones.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl<A> Executor<A> for CountingExecutor
+where
+    A: Send + 'static,
+{
+    async fn execute(&self, _action: A) -> Result<()> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if self.fail {
+            anyhow::bail!("rejected");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    #[tokio::test]
+    async fn mock_provider_answers_canned_and_queued_requests() {
+        let provider = MockProvider::new()
+            .with_gas_price(30)
+            .with_block_number(100)
+            .with_chain_id(5)
+            .with_nonce(7);
+        assert_eq!(provider.get_gas_price().await.unwrap(), 30);
+        assert_eq!(provider.get_block_number().await.unwrap(), 100);
+        assert_eq!(provider.get_chain_id().await.unwrap(), 5);
+        assert_eq!(
+            provider.get_transaction_count(Address::ZERO).await.unwrap(),
+            7
+        );
+
+        provider.set_block_number(101);
+        assert_eq!(provider.get_block_number().await.unwrap(), 101);
+
+        provider.asserter().push_success(&U256::from(42));
+        assert_eq!(
+            provider.get_balance(Address::ZERO).await.unwrap(),
+            U256::from(42)
+        );
+    }
+}
@@ -1,11 +1,13 @@
+use crate::mevshare;
 use alloy::rpc::types::mev::EthSendPrivateTransaction;
 use alloy::rpc::types::{
     eth::Transaction,
-    mev::{EthSendBundle, MevSendBundle, mevshare},
+    mev::{EthSendBundle, MevSendBundle},
 };
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::pin::Pin;
 use tokio_stream::{Stream, StreamExt};
 
@@ -20,6 +22,17 @@ pub type CollectorStream<'a, E> = Pin<Box<dyn Stream<Item = E> + Send + 'a>>;
 pub trait Collector<E>: Send + Sync {
     /// Returns the core event stream for the collector.
     async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, E>>;
+
+    /// Stop any background tasks feeding the streams returned so far, ending
+    /// those streams without waiting for them to be dropped. Defaults to
+    /// doing nothing, for collectors without background tasks.
+    async fn close(&self) {}
+
+    /// A human-readable name for the collector, used in logs and metrics.
+    /// Defaults to the collector's type name.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// Strategy trait, which defines the core logic for each opportunity.
@@ -31,6 +44,12 @@ pub trait Strategy<E, A>: Send + Sync {
 
     /// Process an event, and return an action if needed.
     async fn process_event(&mut self, event: E) -> Vec<A>;
+
+    /// A human-readable name for the strategy, used in logs and metrics.
+    /// Defaults to the strategy's type name.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// Executor trait, responsible for executing actions returned by strategies.
@@ -38,6 +57,12 @@ pub trait Strategy<E, A>: Send + Sync {
 pub trait Executor<A>: Send + Sync {
     /// Execute an action.
     async fn execute(&self, action: A) -> Result<()>;
+
+    /// A human-readable name for the executor, used in logs and metrics.
+    /// Defaults to the executor's type name.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// CollectorMap is a wrapper around a [Collector](Collector) that maps outgoing
@@ -65,6 +90,123 @@ where
         let stream = stream.map(f);
         Ok(Box::pin(stream))
     }
+
+    async fn close(&self) {
+        self.collector.close().await
+    }
+
+    fn name(&self) -> &str {
+        self.collector.name()
+    }
+}
+
+/// An event tagged with the name of the collector that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tagged<E> {
+    pub source: &'static str,
+    pub event: E,
+}
+
+/// TaggedCollector is a wrapper around a [Collector](Collector) that tags each
+/// outgoing event with a source name, so strategies consuming several
+/// collectors can tell them apart.
+pub struct TaggedCollector<E> {
+    collector: Box<dyn Collector<E>>,
+    source: &'static str,
+}
+
+impl<E> TaggedCollector<E> {
+    pub fn new(collector: Box<dyn Collector<E>>, source: &'static str) -> Self {
+        Self { collector, source }
+    }
+}
+
+#[async_trait]
+impl<E> Collector<Tagged<E>> for TaggedCollector<E>
+where
+    E: Send + Sync + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, Tagged<E>>> {
+        let stream = self.collector.get_event_stream().await?;
+        let source = self.source;
+        let stream = stream.map(move |event| Tagged { source, event });
+        Ok(Box::pin(stream))
+    }
+
+    async fn close(&self) {
+        self.collector.close().await
+    }
+
+    fn name(&self) -> &str {
+        self.source
+    }
+}
+
+/// AsyncCollectorMap is a wrapper around a [Collector](Collector) that maps
+/// outgoing events to a different type with an async function, running up to
+/// `concurrency` mappings at once. Events are emitted in the order they were
+/// collected unless [unordered](AsyncCollectorMap::unordered) is set.
+pub struct AsyncCollectorMap<E, F> {
+    collector: Box<dyn Collector<E>>,
+    f: F,
+    concurrency: usize,
+    ordered: bool,
+}
+
+impl<E, F> AsyncCollectorMap<E, F> {
+    pub fn new(collector: Box<dyn Collector<E>>, f: F) -> Self {
+        Self {
+            collector,
+            f,
+            concurrency: 16,
+            ordered: true,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Emit events as soon as their mapping completes, instead of in the
+    /// order they were collected.
+    pub fn unordered(mut self) -> Self {
+        self.ordered = false;
+        self
+    }
+}
+
+#[async_trait]
+impl<E1, E2, F, Fut> Collector<E2> for AsyncCollectorMap<E1, F>
+where
+    E1: Send + Sync + 'static,
+    E2: Send + Sync + 'static,
+    F: Fn(E1) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = E2> + Send + 'static,
+{
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, E2>> {
+        let stream = self.collector.get_event_stream().await?;
+        let stream = stream.map(self.f.clone());
+        if self.ordered {
+            Ok(Box::pin(futures::StreamExt::buffered(
+                stream,
+                self.concurrency,
+            )))
+        } else {
+            Ok(Box::pin(futures::StreamExt::buffer_unordered(
+                stream,
+                self.concurrency,
+            )))
+        }
+    }
+
+    async fn close(&self) {
+        self.collector.close().await
+    }
+
+    fn name(&self) -> &str {
+        self.collector.name()
+    }
 }
 
 /// ExecutorMap is a wrapper around an [Executor](Executor) that maps incoming
@@ -94,6 +236,71 @@ where
             None => Ok(()),
         }
     }
+
+    fn name(&self) -> &str {
+        self.executor.name()
+    }
+}
+
+/// CombinedStrategy runs several [strategies](Strategy) over the same event
+/// stream, and concatenates their actions in the order the strategies were
+/// added.
+pub struct CombinedStrategy<E, A> {
+    strategies: Vec<Box<dyn Strategy<E, A>>>,
+    /// Whether each strategy has synced, so retries only sync the ones that
+    /// failed.
+    synced: Vec<bool>,
+}
+
+impl<E, A> CombinedStrategy<E, A> {
+    pub fn new(strategies: Vec<Box<dyn Strategy<E, A>>>) -> Self {
+        let synced = vec![false; strategies.len()];
+        Self { strategies, synced }
+    }
+
+    pub fn with_strategy(mut self, strategy: Box<dyn Strategy<E, A>>) -> Self {
+        self.strategies.push(strategy);
+        self.synced.push(false);
+        self
+    }
+}
+
+#[async_trait]
+impl<E, A> Strategy<E, A> for CombinedStrategy<E, A>
+where
+    E: Clone + Send + Sync + 'static,
+    A: Send + Sync + 'static,
+{
+    /// Sync every strategy that hasn't synced yet. All of them are attempted
+    /// even if one fails, and the failures are reported together.
+    async fn sync_state(&mut self) -> Result<()> {
+        let mut errors = Vec::new();
+        for (i, strategy) in self.strategies.iter_mut().enumerate() {
+            if self.synced[i] {
+                continue;
+            }
+            match strategy.sync_state().await {
+                Ok(()) => self.synced[i] = true,
+                Err(e) => errors.push(format!("strategy {i} ({}): {e:#}", strategy.name())),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "failed to sync combined strategies: {}",
+                errors.join("; ")
+            ))
+        }
+    }
+
+    async fn process_event(&mut self, event: E) -> Vec<A> {
+        let mut actions = Vec::new();
+        for strategy in &mut self.strategies {
+            actions.extend(strategy.process_event(event.clone()).await);
+        }
+        actions
+    }
 }
 
 /// Convenience enum containing all the events that can be emitted by collectors.
@@ -140,4 +347,132 @@ pub const BUILDERS: &[&str] = &[
 pub const MEV_SHARE: &str = "https://mev-share.flashbots.net";
 pub const MEV_RELAY: &str = "https://relay.flashbots.net";
 
+pub const MEV_SHARE_SEPOLIA: &str = "https://mev-share-sepolia.flashbots.net";
+pub const MEV_RELAY_SEPOLIA: &str = "https://relay-sepolia.flashbots.net";
+pub const FLASHBOTS_BOOST_RELAY: &str = "https://boost-relay.flashbots.net";
+
 pub const TITAN_ENDPOINT: &str = "https://rpc.titanbuilder.xyz";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::ScriptedCollector;
+    use futures::FutureExt;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::oneshot;
+
+    /// A collector that emits a fixed list of events.
+    struct VecCollector(Vec<u64>);
+
+    #[async_trait]
+    impl Collector<u64> for VecCollector {
+        async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, u64>> {
+            Ok(Box::pin(tokio_stream::iter(self.0.clone())))
+        }
+    }
+
+    /// Gates holding back the mapping of each event until the test releases
+    /// it, and the map that takes them.
+    fn gated_double() -> (
+        HashMap<u64, oneshot::Sender<()>>,
+        impl Fn(u64) -> futures::future::BoxFuture<'static, u64> + Clone + Send + Sync,
+    ) {
+        let (senders, receivers): (HashMap<_, _>, HashMap<_, _>) = (1..=3)
+            .map(|event| {
+                let (sender, receiver) = oneshot::channel::<()>();
+                ((event, sender), (event, receiver))
+            })
+            .unzip();
+        let receivers = Arc::new(Mutex::new(receivers));
+        let map = move |event: u64| {
+            let gate = receivers.lock().unwrap().remove(&event).unwrap();
+            Box::pin(async move {
+                let _ = gate.await;
+                event * 2
+            }) as futures::future::BoxFuture<'static, u64>
+        };
+        (senders, map)
+    }
+
+    #[tokio::test]
+    async fn async_collector_map_preserves_order() {
+        // Later events finish first, but are held back until the first one.
+        let (mut gates, map) = gated_double();
+        let collector = Box::new(VecCollector(vec![1, 2, 3]));
+        let map = AsyncCollectorMap::new(collector, map).with_concurrency(3);
+        let mut events = map.get_event_stream().await.unwrap();
+        for event in [3, 2] {
+            gates.remove(&event).unwrap().send(()).unwrap();
+        }
+        assert!(events.next().now_or_never().is_none());
+        gates.remove(&1).unwrap().send(()).unwrap();
+        assert_eq!(events.collect::<Vec<_>>().await, vec![2, 4, 6]);
+
+        // Unordered, each event is emitted as soon as it's released.
+        let (mut gates, map) = gated_double();
+        let collector = Box::new(VecCollector(vec![1, 2, 3]));
+        let map = AsyncCollectorMap::new(collector, map)
+            .with_concurrency(3)
+            .unordered();
+        let mut events = map.get_event_stream().await.unwrap();
+        for (event, doubled) in [(3, 6), (2, 4), (1, 2)] {
+            gates.remove(&event).unwrap().send(()).unwrap();
+            assert_eq!(events.next().await, Some(doubled));
+        }
+    }
+
+    #[tokio::test]
+    async fn tagged_collector_labels_events_with_source() {
+        let collector =
+            TaggedCollector::new(Box::new(ScriptedCollector::new(vec![1u64, 2])), "blocks");
+        assert_eq!(collector.name(), "blocks");
+        let events = collector.get_event_stream().await.unwrap();
+        let tagged = |event| Tagged {
+            source: "blocks",
+            event,
+        };
+        assert_eq!(events.collect::<Vec<_>>().await, vec![tagged(1), tagged(2)]);
+    }
+
+    /// A strategy that fails to sync once, then echoes events with an offset.
+    struct OffsetStrategy {
+        offset: u64,
+        syncs: usize,
+        fail_first_sync: bool,
+    }
+
+    #[async_trait]
+    impl Strategy<u64, u64> for OffsetStrategy {
+        async fn sync_state(&mut self) -> Result<()> {
+            self.syncs += 1;
+            if self.fail_first_sync && self.syncs == 1 {
+                anyhow::bail!("not ready");
+            }
+            Ok(())
+        }
+
+        async fn process_event(&mut self, event: u64) -> Vec<u64> {
+            vec![event + self.offset]
+        }
+    }
+
+    #[tokio::test]
+    async fn combined_strategy_concatenates_and_retries_failed_syncs() {
+        let mut strategy = CombinedStrategy::new(vec![Box::new(OffsetStrategy {
+            offset: 10,
+            syncs: 0,
+            fail_first_sync: false,
+        })])
+        .with_strategy(Box::new(OffsetStrategy {
+            offset: 20,
+            syncs: 0,
+            fail_first_sync: true,
+        }));
+
+        assert!(strategy.sync_state().await.is_err());
+        assert!(strategy.sync_state().await.is_ok());
+        assert_eq!(strategy.synced, vec![true, true]);
+        assert_eq!(strategy.process_event(1).await, vec![11, 21]);
+    }
+}
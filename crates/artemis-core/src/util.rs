@@ -0,0 +1,93 @@
+use alloy::providers::{DynProvider, Provider, ProviderBuilder, WsConnect};
+use alloy::transports::Authorization;
+use anyhow::{Context, Result};
+use std::collections::hash_map::RandomState;
+use std::fmt::Display;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use tracing::warn;
+
+/// Connect a websocket provider, sending `auth` as the `Authorization` header
+/// of the handshake if given. Premium RPC providers usually accept an API key
+/// this way, which keeps it out of the url (and out of logs).
+pub async fn connect_ws_with_auth(
+    url: impl Into<String>,
+    auth: Option<Authorization>,
+) -> Result<DynProvider> {
+    let mut ws = WsConnect::new(url);
+    if let Some(auth) = auth {
+        ws = ws.with_auth(auth);
+    }
+    let provider = ProviderBuilder::new()
+        .connect_ws(ws)
+        .await
+        .context("failed to connect websocket provider")?;
+    Ok(provider.erased())
+}
+
+/// Call `f` up to `attempts` times, sleeping `delay` between failed attempts,
+/// and return the first success or the last error. Meant for cheap, idempotent
+/// provider calls where a transient failure shouldn't drop the whole operation.
+pub async fn retry<T, E, F, Fut>(attempts: usize, delay: Duration, mut f: F) -> Result<T, E>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let attempts = attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < attempts => {
+                warn!("attempt {}/{} failed: {}; retrying", attempt, attempts, e);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A random delay between zero and `delay`, to spread out retries of many
+/// clients that failed at the same time ("full jitter").
+pub fn full_jitter(delay: Duration) -> Duration {
+    // A fresh `RandomState` is randomly keyed, which is random enough here
+    // without pulling in an rng.
+    let random = RandomState::new().build_hasher().finish();
+    delay.mul_f64(random as f64 / u64::MAX as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn retry_returns_first_success() {
+        let mut calls = 0;
+        let result: Result<usize, String> = retry(3, Duration::ZERO, || {
+            calls += 1;
+            let calls = calls;
+            async move {
+                if calls < 3 {
+                    Err(format!("call {calls}"))
+                } else {
+                    Ok(calls)
+                }
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(3));
+
+        let result: Result<(), &str> = retry(2, Duration::ZERO, || async { Err("down") }).await;
+        assert_eq!(result, Err("down"));
+    }
+
+    #[test]
+    fn full_jitter_stays_within_delay() {
+        let delay = Duration::from_millis(100);
+        assert!((0..100).all(|_| full_jitter(delay) <= delay));
+        assert_eq!(full_jitter(Duration::ZERO), Duration::ZERO);
+    }
+}
@@ -0,0 +1,114 @@
+//! Runs the engine end to end with in-memory components: events pushed into a
+//! channel are collected, turned into actions by a strategy, and recorded by
+//! an executor, without any network access.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use artemis_core::engine::Engine;
+use artemis_core::types::{
+    Collector, CollectorMap, CollectorStream, Executor, ExecutorMap, Strategy,
+};
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+#[derive(Debug, Clone)]
+enum Event {
+    Block(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    Submit(u64),
+    Log(String),
+}
+
+/// A collector that emits the events sent to its channel, and ends once the
+/// sender is dropped.
+struct ChannelCollector<E>(Mutex<Option<mpsc::UnboundedReceiver<E>>>);
+
+impl<E> ChannelCollector<E> {
+    fn new() -> (mpsc::UnboundedSender<E>, Self) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (tx, Self(Mutex::new(Some(rx))))
+    }
+}
+
+#[async_trait]
+impl<E: Send + 'static> Collector<E> for ChannelCollector<E> {
+    async fn get_event_stream<'life1>(&self) -> Result<CollectorStream<'life1, E>> {
+        let rx = self.0.lock().unwrap().take().expect("stream already taken");
+        Ok(Box::pin(UnboundedReceiverStream::new(rx)))
+    }
+}
+
+/// Submits every even block, and logs every block.
+struct EvenBlockStrategy;
+
+#[async_trait]
+impl Strategy<Event, Action> for EvenBlockStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, event: Event) -> Vec<Action> {
+        let Event::Block(number) = event;
+        let mut actions = vec![Action::Log(format!("block {number}"))];
+        if number % 2 == 0 {
+            actions.push(Action::Submit(number));
+        }
+        actions
+    }
+}
+
+/// An executor that records the actions it's given.
+struct RecordingExecutor<A>(Arc<Mutex<Vec<A>>>);
+
+#[async_trait]
+impl<A: Send + Sync + 'static> Executor<A> for RecordingExecutor<A> {
+    async fn execute(&self, action: A) -> Result<()> {
+        self.0.lock().unwrap().push(action);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn engine_routes_events_to_executors() {
+    let (events, collector) = ChannelCollector::new();
+    let (submitted, logged) = (Arc::new(Mutex::new(vec![])), Arc::new(Mutex::new(vec![])));
+
+    let mut engine: Engine<Event, Action> = Engine::new();
+    engine.add_collector(Box::new(CollectorMap::new(
+        Box::new(collector),
+        Event::Block,
+    )));
+    engine.add_strategy(Box::new(EvenBlockStrategy));
+    engine.add_executor(Box::new(ExecutorMap::new(
+        Box::new(RecordingExecutor(submitted.clone())),
+        |action| match action {
+            Action::Submit(number) => Some(number),
+            _ => None,
+        },
+    )));
+    engine.add_executor(Box::new(ExecutorMap::new(
+        Box::new(RecordingExecutor(logged.clone())),
+        |action| match action {
+            Action::Log(line) => Some(line),
+            _ => None,
+        },
+    )));
+
+    for number in 1..=4u64 {
+        events.send(number).unwrap();
+    }
+    drop(events);
+    engine.run_for(Duration::from_secs(10)).await.unwrap();
+
+    assert_eq!(*submitted.lock().unwrap(), vec![2, 4]);
+    assert_eq!(
+        *logged.lock().unwrap(),
+        vec!["block 1", "block 2", "block 3", "block 4"]
+    );
+}
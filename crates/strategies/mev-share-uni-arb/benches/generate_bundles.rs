@@ -0,0 +1,45 @@
+//! Latency of turning an opportunity into bundles, against a mock provider so
+//! that only the strategy's own work is measured. Run with
+//! `cargo bench -p mev-share-uni-arb`.
+
+use std::sync::Arc;
+
+use alloy::network::EthereumWallet;
+use alloy::primitives::{Address, B256, U256, address, aliases::U160};
+use alloy::signers::local::PrivateKeySigner;
+use artemis_core::test_util::MockProvider;
+use artemis_core::types::Strategy;
+use criterion::{Criterion, criterion_group, criterion_main};
+use mev_share_uni_arb::strategy::MevShareUniArb;
+
+/// A v3 pool listed in the strategy's pool file.
+const V3_POOL: Address = address!("0x640784681a4fdf860f9f266bd4c063fc2efc216e");
+
+fn bench_generate_bundles(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let provider = Arc::new(
+        MockProvider::new()
+            .with_gas_price(20_000_000_000)
+            .with_block_number(18_000_000),
+    );
+    let wallet = EthereumWallet::from(PrivateKeySigner::random());
+    let mut strategy = MevShareUniArb::new(provider, wallet, Address::repeat_byte(1));
+    runtime.block_on(strategy.sync_state()).unwrap();
+    let sqrt_price_x96 = U160::from(1u128 << 96);
+
+    // Without a profit threshold no v2 reserves are fetched, so every size
+    // is signed.
+    let mut group = c.benchmark_group("generate_bundles");
+    group.bench_function("default_sizes", |b| {
+        b.to_async(&runtime)
+            .iter(|| strategy.generate_bundles_with_price(V3_POOL, B256::ZERO, sqrt_price_x96));
+    });
+    group.bench_function("sign_one", |b| {
+        b.to_async(&runtime)
+            .iter(|| strategy.build_arb_tx(V3_POOL, U256::from(1_000_000_000_000_000u64)));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate_bundles);
+criterion_main!(benches);
@@ -21,6 +21,20 @@ pub mod blind_arb {
                 uint256 percentageToPayToCoinbase
             ) external;
 
+            function executeArb__token0(
+                address baseToken,
+                address v2Pair,
+                address v3Pair,
+                uint256 amountIn
+            ) external;
+
+            function executeArb__token1(
+                address baseToken,
+                address v2Pair,
+                address v3Pair,
+                uint256 amountIn
+            ) external;
+
             function uniswapV3SwapCallback(
                 int256 amount0Delta,
                 int256 amount1Delta,
@@ -31,6 +45,8 @@ pub mod blind_arb {
 
             function withdrawWETHToOwner() external;
 
+            function withdrawTokenToOwner(address token) external;
+
             function owner() external view returns (address);
 
             function transferOwnership(address newOwner) external;
@@ -66,6 +82,10 @@ pub mod i_uniswap_v2_pair {
                 view
                 returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast);
 
+            function token0() external view returns (address);
+
+            function token1() external view returns (address);
+
             function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes data) external;
         }
     }
@@ -77,6 +97,37 @@ pub mod i_uniswap_v3_pool {
     sol! {
         #[sol(rpc)]
         interface IUniswapV3Pool {
+            event Swap(
+                address indexed sender,
+                address indexed recipient,
+                int256 amount0,
+                int256 amount1,
+                uint160 sqrtPriceX96,
+                uint128 liquidity,
+                int24 tick
+            );
+
+            function slot0()
+                external
+                view
+                returns (
+                    uint160 sqrtPriceX96,
+                    int24 tick,
+                    uint16 observationIndex,
+                    uint16 observationCardinality,
+                    uint16 observationCardinalityNext,
+                    uint8 feeProtocol,
+                    bool unlocked
+                );
+
+            function liquidity() external view returns (uint128);
+
+            function token0() external view returns (address);
+
+            function token1() external view returns (address);
+
+            function fee() external view returns (uint24);
+
             function swap(
                 address recipient,
                 bool zeroForOne,
@@ -102,3 +153,71 @@ pub mod i_uniswap_v3_swap_callback {
         }
     }
 }
+
+pub mod i_uniswap_v2_router {
+    use alloy::sol;
+
+    sol! {
+        interface IUniswapV2Router02 {
+            function swapExactTokensForTokens(
+                uint256 amountIn,
+                uint256 amountOutMin,
+                address[] path,
+                address to,
+                uint256 deadline
+            ) external returns (uint256[] amounts);
+
+            function swapExactETHForTokens(
+                uint256 amountOutMin,
+                address[] path,
+                address to,
+                uint256 deadline
+            ) external payable returns (uint256[] amounts);
+
+            function swapExactTokensForETH(
+                uint256 amountIn,
+                uint256 amountOutMin,
+                address[] path,
+                address to,
+                uint256 deadline
+            ) external returns (uint256[] amounts);
+        }
+    }
+}
+
+pub mod i_swap_router {
+    use alloy::sol;
+
+    sol! {
+        interface ISwapRouter {
+            struct ExactInputSingleParams {
+                address tokenIn;
+                address tokenOut;
+                uint24 fee;
+                address recipient;
+                uint256 deadline;
+                uint256 amountIn;
+                uint256 amountOutMinimum;
+                uint160 sqrtPriceLimitX96;
+            }
+
+            struct ExactInputParams {
+                bytes path;
+                address recipient;
+                uint256 deadline;
+                uint256 amountIn;
+                uint256 amountOutMinimum;
+            }
+
+            function exactInputSingle(ExactInputSingleParams params)
+                external
+                payable
+                returns (uint256 amountOut);
+
+            function exactInput(ExactInputParams params)
+                external
+                payable
+                returns (uint256 amountOut);
+        }
+    }
+}
@@ -2,6 +2,8 @@
 pragma solidity ^0.8.13;
 
 import {Owned} from "solmate/auth/Owned.sol";
+import {ERC20} from "solmate/tokens/ERC20.sol";
+import {SafeTransferLib} from "solmate/utils/SafeTransferLib.sol";
 
 interface IWETH {
     function deposit() external payable;
@@ -13,6 +15,10 @@ interface IWETH {
     function transfer(address, uint256) external returns (bool);
 }
 
+interface IERC20 {
+    function balanceOf(address) external view returns (uint256);
+}
+
 interface IUniswapV2Pair {
     function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast);
 
@@ -38,12 +44,17 @@ interface IUniswapV3SwapCallback {
 }
 
 contract BlindArb is Owned, IUniswapV3SwapCallback {
+    // Tokens like USDT return nothing from `transfer`, so go through
+    // SafeTransferLib rather than decoding a bool.
+    using SafeTransferLib for ERC20;
+
     IWETH internal constant WETH = IWETH(0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2);
 
     uint160 internal constant MIN_SQRT_RATIO = 4295128739;
     uint160 internal constant MAX_SQRT_RATIO = 1461446703485210103287273052203988822378723970342;
 
     address callBackAddress = address(0);
+    address callBackToken = address(0);
 
     constructor() Owned(msg.sender) {}
 
@@ -53,34 +64,8 @@ contract BlindArb is Owned, IUniswapV3SwapCallback {
         uint256 amountIn,
         uint256 percentageToPayToCoinbase
     ) public onlyOwner {
-        callBackAddress = v3Pair;
-
-        uint256 balanceBefore = WETH.balanceOf(address(this));
-
-        // Swap on V3 
-        (, int256 tokenOut) = IUniswapV3Pool(v3Pair).swap(
-            v2Pair,
-            true,
-            int256(amountIn),
-            MIN_SQRT_RATIO + 1,
-            ""
-        );
-
-        uint256 tokenOutExact = uint256(- tokenOut);
-
-        IUniswapV2Pair v2Pair = IUniswapV2Pair(v2Pair);
-        (uint256 v2Reserve0, uint256 v2Reserve1,) = v2Pair.getReserves();
-        uint256 v2AmountOut = getAmountOut(tokenOutExact, v2Reserve1, v2Reserve0);
-        v2Pair.swap(v2AmountOut, 0, address(this), "");
-
-        uint256 balanceAfter = WETH.balanceOf(address(this));
-        uint profit = balanceAfter - balanceBefore;
-        uint profitToCoinbase = profit * percentageToPayToCoinbase / 100;
-        WETH.withdraw(profitToCoinbase);
-        block.coinbase.transfer(profitToCoinbase);
-        require(balanceAfter - profitToCoinbase > balanceBefore, "arb failed");
-
-        callBackAddress = address(0);
+        uint256 profit = arb(address(WETH), v2Pair, v3Pair, amountIn, true);
+        payCoinbase(profit, percentageToPayToCoinbase);
     }
 
     function executeArb__WETH_token1(
@@ -89,37 +74,81 @@ contract BlindArb is Owned, IUniswapV3SwapCallback {
         uint256 amountIn,
         uint256 percentageToPayToCoinbase
     ) public onlyOwner {
+        uint256 profit = arb(address(WETH), v2Pair, v3Pair, amountIn, false);
+        payCoinbase(profit, percentageToPayToCoinbase);
+    }
+
+    /// Arb through `baseToken` instead of WETH, e.g. a stablecoin. The profit
+    /// is kept in `baseToken`, so nothing is paid to the coinbase.
+    function executeArb__token0(
+        address baseToken,
+        address v2Pair,
+        address v3Pair,
+        uint256 amountIn
+    ) public onlyOwner {
+        uint256 profit = arb(baseToken, v2Pair, v3Pair, amountIn, true);
+        require(profit > 0, "arb failed");
+    }
+
+    function executeArb__token1(
+        address baseToken,
+        address v2Pair,
+        address v3Pair,
+        uint256 amountIn
+    ) public onlyOwner {
+        uint256 profit = arb(baseToken, v2Pair, v3Pair, amountIn, false);
+        require(profit > 0, "arb failed");
+    }
+
+    /// Sell `amountIn` of `baseToken` on v3 and buy it back on v2, returning
+    /// the amount of `baseToken` gained. Reverts on a loss.
+    function arb(
+        address baseToken,
+        address v2Pair,
+        address v3Pair,
+        uint256 amountIn,
+        bool baseToken0
+    ) internal returns (uint256) {
         callBackAddress = v3Pair;
+        callBackToken = baseToken;
 
-        uint256 balanceBefore = WETH.balanceOf(address(this));
+        uint256 balanceBefore = IERC20(baseToken).balanceOf(address(this));
 
         // Swap on V3 
-        (int256 tokenOut,) = IUniswapV3Pool(v3Pair).swap(
+        (int256 amount0, int256 amount1) = IUniswapV3Pool(v3Pair).swap(
             v2Pair,
-            false,
+            baseToken0,
             int256(amountIn),
-            MAX_SQRT_RATIO - 1,
+            baseToken0 ? MIN_SQRT_RATIO + 1 : MAX_SQRT_RATIO - 1,
             ""
         );
 
-        uint256 tokenOutExact = uint256(- tokenOut);
+        uint256 tokenOutExact = uint256(- (baseToken0 ? amount1 : amount0));
+
+        IUniswapV2Pair pair = IUniswapV2Pair(v2Pair);
+        (uint256 v2Reserve0, uint256 v2Reserve1,) = pair.getReserves();
+        if (baseToken0) {
+            pair.swap(getAmountOut(tokenOutExact, v2Reserve1, v2Reserve0), 0, address(this), "");
+        } else {
+            pair.swap(0, getAmountOut(tokenOutExact, v2Reserve0, v2Reserve1), address(this), "");
+        }
+
+        callBackAddress = address(0);
+        callBackToken = address(0);
 
-        IUniswapV2Pair v2Pair = IUniswapV2Pair(v2Pair);
-        (uint256 v2Reserve0, uint256 v2Reserve1,) = v2Pair.getReserves();
-        uint256 v2AmountOut = getAmountOut(tokenOutExact, v2Reserve0, v2Reserve1);
-        v2Pair.swap(0, v2AmountOut, address(this), "");
+        return IERC20(baseToken).balanceOf(address(this)) - balanceBefore;
+    }
 
-        uint256 balanceAfter = WETH.balanceOf(address(this));
-        uint profit = balanceAfter - balanceBefore;
+    /// Pay `percentageToPayToCoinbase` of a WETH `profit` to the coinbase, and
+    /// revert unless some profit is left over.
+    function payCoinbase(uint256 profit, uint256 percentageToPayToCoinbase) internal {
         uint profitToCoinbase = profit * percentageToPayToCoinbase / 100;
         WETH.withdraw(profitToCoinbase);
         block.coinbase.transfer(profitToCoinbase);
-        require(balanceAfter - profitToCoinbase > balanceBefore, "arb failed");
-
-        callBackAddress = address(0);
+        require(profit > profitToCoinbase, "arb failed");
     }
 
-    /// Pay back WETH
+    /// Pay back the base token
     function uniswapV3SwapCallback(
         int256 amount0Delta,
         int256 amount1Delta,
@@ -127,7 +156,7 @@ contract BlindArb is Owned, IUniswapV3SwapCallback {
     ) external override {
         require(msg.sender == callBackAddress, "invalid sender");
         uint256 amountOwed = uint256(amount0Delta > 0 ? amount0Delta : amount1Delta);
-        WETH.transfer(callBackAddress, amountOwed);
+        ERC20(callBackToken).safeTransfer(callBackAddress, amountOwed);
     }
 
     function getAmountOut(uint256 amountIn, uint256 reserveIn, uint256 reserveOut)
@@ -146,6 +175,11 @@ contract BlindArb is Owned, IUniswapV3SwapCallback {
         WETH.transfer(msg.sender, balance);
     }
 
+    function withdrawTokenToOwner(address token) external onlyOwner {
+        uint256 balance = IERC20(token).balanceOf(address(this));
+        ERC20(token).safeTransfer(msg.sender, balance);
+    }
+
     function withdrawETHToOwner() external onlyOwner {
         uint256 balance = address(this).balance;
         payable(msg.sender).transfer(balance);
@@ -0,0 +1,386 @@
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use alloy::{
+    network::{Ethereum, NetworkWallet},
+    primitives::{Address, U256},
+    providers::Provider,
+    rpc::types::mev::PrivacyHint,
+    signers::Signer,
+};
+use anyhow::Result;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+use artemis_core::executors::mev_share_executor::SubmittedBundle;
+use artemis_core::signer::SharedSigner;
+
+use crate::strategy::{
+    BidSchedule, BlockTarget, BuilderWeights, Cooldown, MevShareUniArb, PoolFilter,
+};
+use crate::types::{BundleSubmission, OpportunityDetected};
+
+/// Builder for [MevShareUniArb], collecting every tuning option before the
+/// strategy is constructed. Options left unset keep the strategy's defaults.
+pub struct MevShareUniArbBuilder<P, W> {
+    provider: Arc<P>,
+    wallet: W,
+    arb_contract_address: Address,
+    size_range: Option<(u128, u128, usize)>,
+    balance_percentages: Option<Vec<f64>>,
+    min_profit_wei: Option<U256>,
+    block_target: Option<BlockTarget>,
+    cooldown: Option<Cooldown>,
+    signers: Option<Vec<W>>,
+    submission_sender: Option<UnboundedSender<BundleSubmission>>,
+    opportunity_sender: Option<UnboundedSender<OpportunityDetected>>,
+    submitted_bundles: Option<UnboundedReceiver<SubmittedBundle>>,
+    eth_bundles: bool,
+    max_base_fee: Option<u64>,
+    max_gas_price_wei: Option<u128>,
+    builders: Option<Vec<String>>,
+    builder_weights: Option<Arc<RwLock<BuilderWeights>>>,
+    privacy_hints: Option<PrivacyHint>,
+    coinbase_payment: Option<(u64, U256)>,
+    user_refund_percent: Option<u64>,
+    bid_schedule: Option<BidSchedule>,
+    bundle_simulation: Option<(Arc<P>, SharedSigner)>,
+    pool_capacity: Option<usize>,
+    pools_path: Option<PathBuf>,
+    pool_filter: Option<Arc<RwLock<PoolFilter>>>,
+    refund: Option<(u64, Option<Address>)>,
+    refund_split: Option<Vec<(Address, u8)>>,
+    verify_pools: bool,
+    validate_pools: bool,
+    submit_best_only: bool,
+    max_bundles_per_event: Option<usize>,
+    wrapped_native: Option<Address>,
+    sign_concurrency: Option<usize>,
+}
+
+impl<P, W> MevShareUniArbBuilder<P, W>
+where
+    P: Provider + Send + Sync + 'static,
+    W: NetworkWallet<Ethereum> + Clone + Send + Sync + 'static,
+{
+    pub fn new(provider: Arc<P>, wallet: W, arb_contract_address: Address) -> Self {
+        Self {
+            provider,
+            wallet,
+            arb_contract_address,
+            size_range: None,
+            balance_percentages: None,
+            min_profit_wei: None,
+            block_target: None,
+            cooldown: None,
+            signers: None,
+            submission_sender: None,
+            opportunity_sender: None,
+            submitted_bundles: None,
+            eth_bundles: false,
+            max_base_fee: None,
+            max_gas_price_wei: None,
+            builders: None,
+            builder_weights: None,
+            privacy_hints: None,
+            coinbase_payment: None,
+            user_refund_percent: None,
+            bid_schedule: None,
+            bundle_simulation: None,
+            pool_capacity: None,
+            pools_path: None,
+            pool_filter: None,
+            refund: None,
+            refund_split: None,
+            verify_pools: false,
+            validate_pools: false,
+            submit_best_only: false,
+            max_bundles_per_event: None,
+            wrapped_native: None,
+            sign_concurrency: None,
+        }
+    }
+
+    /// See [MevShareUniArb::with_size_range].
+    pub fn size_range(mut self, min_size: u128, max_size: u128, steps: usize) -> Self {
+        self.size_range = Some((min_size, max_size, steps));
+        self
+    }
+
+    /// See [MevShareUniArb::with_balance_percentages].
+    pub fn balance_percentages(mut self, percentages: Vec<f64>) -> Self {
+        self.balance_percentages = Some(percentages);
+        self
+    }
+
+    /// See [MevShareUniArb::with_min_profit].
+    pub fn min_profit(mut self, min_profit_wei: U256) -> Self {
+        self.min_profit_wei = Some(min_profit_wei);
+        self
+    }
+
+    /// See [MevShareUniArb::with_block_window].
+    pub fn block_window(mut self, min_offset: u64, max_offset: u64) -> Self {
+        self.block_target = Some(BlockTarget::Window {
+            min: min_offset,
+            max: max_offset,
+        });
+        self
+    }
+
+    /// See [MevShareUniArb::with_block_target].
+    pub fn block_target(mut self, target: BlockTarget) -> Self {
+        self.block_target = Some(target);
+        self
+    }
+
+    /// See [MevShareUniArb::with_cooldown].
+    pub fn cooldown(mut self, cooldown: Cooldown) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+
+    /// See [MevShareUniArb::with_signers].
+    pub fn signers(mut self, signers: Vec<W>) -> Self {
+        self.signers = Some(signers);
+        self
+    }
+
+    /// See [MevShareUniArb::with_submission_sender].
+    pub fn submission_sender(mut self, sender: UnboundedSender<BundleSubmission>) -> Self {
+        self.submission_sender = Some(sender);
+        self
+    }
+
+    /// See [MevShareUniArb::with_opportunity_sender].
+    pub fn opportunity_sender(mut self, sender: UnboundedSender<OpportunityDetected>) -> Self {
+        self.opportunity_sender = Some(sender);
+        self
+    }
+
+    /// See [MevShareUniArb::with_bundle_cancellation].
+    pub fn bundle_cancellation(mut self, receiver: UnboundedReceiver<SubmittedBundle>) -> Self {
+        self.submitted_bundles = Some(receiver);
+        self
+    }
+
+    /// See [MevShareUniArb::with_eth_bundles].
+    pub fn eth_bundles(mut self, eth_bundles: bool) -> Self {
+        self.eth_bundles = eth_bundles;
+        self
+    }
+
+    /// See [MevShareUniArb::with_max_base_fee].
+    pub fn max_base_fee(mut self, max_base_fee: u64) -> Self {
+        self.max_base_fee = Some(max_base_fee);
+        self
+    }
+
+    /// See [MevShareUniArb::with_max_gas_price].
+    pub fn max_gas_price(mut self, max_gas_price_wei: u128) -> Self {
+        self.max_gas_price_wei = Some(max_gas_price_wei);
+        self
+    }
+
+    /// See [MevShareUniArb::with_builders].
+    pub fn builders(mut self, builders: Vec<String>) -> Self {
+        self.builders = Some(builders);
+        self
+    }
+
+    /// See [MevShareUniArb::with_builder_weights].
+    pub fn builder_weights(mut self, weights: Arc<RwLock<BuilderWeights>>) -> Self {
+        self.builder_weights = Some(weights);
+        self
+    }
+
+    /// See [MevShareUniArb::with_privacy_hints].
+    pub fn privacy_hints(mut self, hints: PrivacyHint) -> Self {
+        self.privacy_hints = Some(hints);
+        self
+    }
+
+    /// See [MevShareUniArb::with_coinbase_payment].
+    pub fn coinbase_payment(mut self, bid_percentage: u64, min_payment_wei: U256) -> Self {
+        self.coinbase_payment = Some((bid_percentage, min_payment_wei));
+        self
+    }
+
+    /// See [MevShareUniArb::with_user_refund_percent].
+    pub fn user_refund_percent(mut self, percent: u64) -> Self {
+        self.user_refund_percent = Some(percent);
+        self
+    }
+
+    /// See [MevShareUniArb::with_bid_schedule].
+    pub fn bid_schedule(mut self, schedule: BidSchedule) -> Self {
+        self.bid_schedule = Some(schedule);
+        self
+    }
+
+    /// See [MevShareUniArb::with_bundle_simulation].
+    pub fn bundle_simulation(
+        mut self,
+        relay: Arc<P>,
+        auth_signer: impl Signer + Send + Sync + 'static,
+    ) -> Self {
+        self.bundle_simulation = Some((relay, SharedSigner::new(auth_signer)));
+        self
+    }
+
+    /// See [MevShareUniArb::with_pools_path].
+    pub fn pools_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.pools_path = Some(path.into());
+        self
+    }
+
+    /// See [MevShareUniArb::with_pool_capacity].
+    pub fn pool_capacity(mut self, capacity: usize) -> Self {
+        self.pool_capacity = Some(capacity);
+        self
+    }
+
+    /// See [MevShareUniArb::with_pool_filter].
+    pub fn pool_filter(mut self, pool_filter: Arc<RwLock<PoolFilter>>) -> Self {
+        self.pool_filter = Some(pool_filter);
+        self
+    }
+
+    /// See [MevShareUniArb::with_refund].
+    pub fn refund(mut self, percent: u64, recipient: Option<Address>) -> Self {
+        self.refund = Some((percent, recipient));
+        self
+    }
+
+    /// See [MevShareUniArb::with_refund_split]. The split is validated by
+    /// [build](Self::build).
+    pub fn refund_split(mut self, split: Vec<(Address, u8)>) -> Self {
+        self.refund_split = Some(split);
+        self
+    }
+
+    /// See [MevShareUniArb::with_pool_verification].
+    pub fn verify_pools(mut self, verify_pools: bool) -> Self {
+        self.verify_pools = verify_pools;
+        self
+    }
+
+    /// See [MevShareUniArb::with_pool_validation].
+    pub fn validate_pools(mut self, validate_pools: bool) -> Self {
+        self.validate_pools = validate_pools;
+        self
+    }
+
+    /// See [MevShareUniArb::with_submit_best_only].
+    pub fn submit_best_only(mut self, submit_best_only: bool) -> Self {
+        self.submit_best_only = submit_best_only;
+        self
+    }
+
+    /// See [MevShareUniArb::with_max_bundles_per_event].
+    pub fn max_bundles_per_event(mut self, max: usize) -> Self {
+        self.max_bundles_per_event = Some(max);
+        self
+    }
+
+    /// See [MevShareUniArb::with_wrapped_native].
+    pub fn wrapped_native(mut self, wrapped_native: Address) -> Self {
+        self.wrapped_native = Some(wrapped_native);
+        self
+    }
+
+    /// See [MevShareUniArb::with_sign_concurrency].
+    pub fn sign_concurrency(mut self, concurrency: usize) -> Self {
+        self.sign_concurrency = Some(concurrency);
+        self
+    }
+
+    /// Construct the strategy. Fails if the refund split adds up to more than
+    /// 100 percent.
+    pub fn build(self) -> Result<MevShareUniArb<P, W>> {
+        let mut strategy =
+            MevShareUniArb::new(self.provider, self.wallet, self.arb_contract_address)
+                .with_eth_bundles(self.eth_bundles)
+                .with_pool_verification(self.verify_pools)
+                .with_pool_validation(self.validate_pools)
+                .with_submit_best_only(self.submit_best_only);
+        if let Some(max) = self.max_bundles_per_event {
+            strategy = strategy.with_max_bundles_per_event(max);
+        }
+        if let Some((min_size, max_size, steps)) = self.size_range {
+            strategy = strategy.with_size_range(min_size, max_size, steps);
+        }
+        if let Some(percentages) = self.balance_percentages {
+            strategy = strategy.with_balance_percentages(percentages);
+        }
+        if let Some(min_profit_wei) = self.min_profit_wei {
+            strategy = strategy.with_min_profit(min_profit_wei);
+        }
+        if let Some(target) = self.block_target {
+            strategy = strategy.with_block_target(target);
+        }
+        if let Some(cooldown) = self.cooldown {
+            strategy = strategy.with_cooldown(cooldown);
+        }
+        if let Some(signers) = self.signers {
+            strategy = strategy.with_signers(signers);
+        }
+        if let Some(sender) = self.submission_sender {
+            strategy = strategy.with_submission_sender(sender);
+        }
+        if let Some(sender) = self.opportunity_sender {
+            strategy = strategy.with_opportunity_sender(sender);
+        }
+        if let Some(receiver) = self.submitted_bundles {
+            strategy = strategy.with_bundle_cancellation(receiver);
+        }
+        if let Some(max_base_fee) = self.max_base_fee {
+            strategy = strategy.with_max_base_fee(max_base_fee);
+        }
+        if let Some(max_gas_price_wei) = self.max_gas_price_wei {
+            strategy = strategy.with_max_gas_price(max_gas_price_wei);
+        }
+        if let Some(builders) = self.builders {
+            strategy = strategy.with_builders(builders);
+        }
+        if let Some(weights) = self.builder_weights {
+            strategy = strategy.with_builder_weights(weights);
+        }
+        if let Some(hints) = self.privacy_hints {
+            strategy = strategy.with_privacy_hints(hints);
+        }
+        if let Some((bid_percentage, min_payment_wei)) = self.coinbase_payment {
+            strategy = strategy.with_coinbase_payment(bid_percentage, min_payment_wei);
+        }
+        if let Some(percent) = self.user_refund_percent {
+            strategy = strategy.with_user_refund_percent(percent);
+        }
+        if let Some(schedule) = self.bid_schedule {
+            strategy = strategy.with_bid_schedule(schedule);
+        }
+        if let Some((relay, auth_signer)) = self.bundle_simulation {
+            strategy = strategy.with_bundle_simulation(relay, auth_signer);
+        }
+        if let Some(capacity) = self.pool_capacity {
+            strategy = strategy.with_pool_capacity(capacity);
+        }
+        if let Some(path) = self.pools_path {
+            strategy = strategy.with_pools_path(path);
+        }
+        if let Some(pool_filter) = self.pool_filter {
+            strategy = strategy.with_pool_filter(pool_filter);
+        }
+        if let Some((percent, recipient)) = self.refund {
+            strategy = strategy.with_refund(percent, recipient);
+        }
+        if let Some(split) = self.refund_split {
+            strategy = strategy.with_refund_split(split)?;
+        }
+        if let Some(wrapped_native) = self.wrapped_native {
+            strategy = strategy.with_wrapped_native(wrapped_native);
+        }
+        if let Some(concurrency) = self.sign_concurrency {
+            strategy = strategy.with_sign_concurrency(concurrency);
+        }
+        Ok(strategy)
+    }
+}
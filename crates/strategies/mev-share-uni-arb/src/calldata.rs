@@ -0,0 +1,135 @@
+use alloy::primitives::{Address, U256};
+use alloy::sol_types::SolCall;
+use mev_share_bindings::{i_swap_router::ISwapRouter, i_uniswap_v2_router::IUniswapV2Router02};
+
+/// Size of a token address in a packed uniswap v3 path.
+const ADDRESS_SIZE: usize = 20;
+/// Size of a fee tier in a packed uniswap v3 path.
+const FEE_SIZE: usize = 3;
+
+/// The parameters of a victim's exact-input swap through a uniswap router.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedSwap {
+    /// Amount of the first token in `path` sold. `None` when it's the ETH
+    /// sent with the transaction, which isn't part of the calldata.
+    pub amount_in: Option<U256>,
+    /// Minimum amount of the last token in `path` bought.
+    pub amount_out_min: U256,
+    /// Tokens swapped through, from the token sold to the token bought.
+    pub path: Vec<Address>,
+    pub recipient: Address,
+}
+
+/// Decode a call to one of the exact-input swap functions of the uniswap v2
+/// router. Returns `None` for any other calldata.
+pub fn decode_univ2_swap(calldata: &[u8]) -> Option<DecodedSwap> {
+    if let Ok(call) = IUniswapV2Router02::swapExactTokensForTokensCall::abi_decode(calldata) {
+        return Some(DecodedSwap {
+            amount_in: Some(call.amountIn),
+            amount_out_min: call.amountOutMin,
+            path: call.path,
+            recipient: call.to,
+        });
+    }
+    if let Ok(call) = IUniswapV2Router02::swapExactETHForTokensCall::abi_decode(calldata) {
+        return Some(DecodedSwap {
+            amount_in: None,
+            amount_out_min: call.amountOutMin,
+            path: call.path,
+            recipient: call.to,
+        });
+    }
+    let call = IUniswapV2Router02::swapExactTokensForETHCall::abi_decode(calldata).ok()?;
+    Some(DecodedSwap {
+        amount_in: Some(call.amountIn),
+        amount_out_min: call.amountOutMin,
+        path: call.path,
+        recipient: call.to,
+    })
+}
+
+/// Decode a call to `exactInputSingle` or `exactInput` on the uniswap v3 swap
+/// router. Returns `None` for any other calldata.
+pub fn decode_univ3_swap(calldata: &[u8]) -> Option<DecodedSwap> {
+    if let Ok(call) = ISwapRouter::exactInputSingleCall::abi_decode(calldata) {
+        let params = call.params;
+        return Some(DecodedSwap {
+            amount_in: Some(params.amountIn),
+            amount_out_min: params.amountOutMinimum,
+            path: vec![params.tokenIn, params.tokenOut],
+            recipient: params.recipient,
+        });
+    }
+    let params = ISwapRouter::exactInputCall::abi_decode(calldata)
+        .ok()?
+        .params;
+    Some(DecodedSwap {
+        amount_in: Some(params.amountIn),
+        amount_out_min: params.amountOutMinimum,
+        path: decode_v3_path(&params.path)?,
+        recipient: params.recipient,
+    })
+}
+
+/// Decode the tokens of a packed `token (fee token)*` uniswap v3 path.
+fn decode_v3_path(path: &[u8]) -> Option<Vec<Address>> {
+    let hops = path.len().checked_sub(ADDRESS_SIZE)?;
+    if hops == 0 || hops % (FEE_SIZE + ADDRESS_SIZE) != 0 {
+        return None;
+    }
+    Some(
+        path.chunks(ADDRESS_SIZE + FEE_SIZE)
+            .map(|chunk| Address::from_slice(&chunk[..ADDRESS_SIZE]))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, aliases::U24};
+
+    #[test]
+    fn decodes_router_swaps() {
+        let (weth, usdc, dai) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+
+        let calldata = IUniswapV2Router02::swapExactTokensForTokensCall {
+            amountIn: U256::from(100),
+            amountOutMin: U256::from(90),
+            path: vec![weth, usdc],
+            to: dai,
+            deadline: U256::MAX,
+        }
+        .abi_encode();
+        let swap = decode_univ2_swap(&calldata).unwrap();
+        assert_eq!(swap.amount_in, Some(U256::from(100)));
+        assert_eq!(swap.path, vec![weth, usdc]);
+        assert_eq!(decode_univ3_swap(&calldata), None);
+
+        let mut path = weth.to_vec();
+        path.extend_from_slice(&U24::from(500).to_be_bytes::<3>());
+        path.extend_from_slice(usdc.as_slice());
+        path.extend_from_slice(&U24::from(100).to_be_bytes::<3>());
+        path.extend_from_slice(dai.as_slice());
+        let calldata = ISwapRouter::exactInputCall {
+            params: ISwapRouter::ExactInputParams {
+                path: Bytes::from(path),
+                recipient: dai,
+                deadline: U256::MAX,
+                amountIn: U256::from(100),
+                amountOutMinimum: U256::from(90),
+            },
+        }
+        .abi_encode();
+        let swap = decode_univ3_swap(&calldata).unwrap();
+        assert_eq!(swap.path, vec![weth, usdc, dai]);
+        assert_eq!(swap.recipient, dai);
+
+        assert_eq!(decode_univ2_swap(&[0xde, 0xad, 0xbe, 0xef]), None);
+        assert_eq!(decode_univ3_swap(&[]), None);
+    }
+}
@@ -3,6 +3,18 @@
 //! that touch a v3 pool that we have a v2 pool for. We then submit a series of backruns
 //! of varying sizes, hoping that one of them will be profitable.
 
+/// This module contains a builder for configuring the strategy.
+pub mod builder;
+
+/// This module contains decoders for victim router calldata.
+pub mod calldata;
+
+/// This module contains helpers for reading uniswap v3 pool state.
+pub mod pool;
+
+/// This module contains rough profit estimates and pnl tracking for the strategy.
+pub mod profit;
+
 /// This module contains the core strategy implementation.
 pub mod strategy;
 
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use alloy::primitives::{Address, U256, aliases::U160, uint};
+use alloy::providers::Provider;
+use anyhow::{Context, Result, ensure};
+use mev_share_bindings::i_uniswap_v3_pool::IUniswapV3Pool;
+
+/// Lowest tick a uniswap v3 pool can be at.
+pub const MIN_TICK: i32 = -887_272;
+/// Highest tick a uniswap v3 pool can be at.
+pub const MAX_TICK: i32 = 887_272;
+/// Lowest sqrt price a uniswap v3 pool can be at, the sqrt price at [MIN_TICK].
+pub const MIN_SQRT_RATIO: U256 = uint!(4295128739_U256);
+/// Highest sqrt price a uniswap v3 pool can be at, the sqrt price at [MAX_TICK].
+pub const MAX_SQRT_RATIO: U256 = uint!(1461446703485210103287273052203988822378723970342_U256);
+
+/// The current price and in-range liquidity of a uniswap v3 pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct V3PoolState {
+    pub sqrt_price_x96: U160,
+    pub tick: i32,
+    /// Liquidity in range at the current tick.
+    pub liquidity: u128,
+}
+
+impl V3PoolState {
+    /// Check that the state is one a live pool can be in: initialized, with a
+    /// price and tick within uniswap's bounds, and some liquidity in range.
+    pub fn validate(&self) -> Result<()> {
+        let sqrt_price = U256::from(self.sqrt_price_x96);
+        ensure!(
+            (MIN_SQRT_RATIO..MAX_SQRT_RATIO).contains(&sqrt_price),
+            "sqrt price {} out of range",
+            sqrt_price
+        );
+        ensure!(
+            (MIN_TICK..=MAX_TICK).contains(&self.tick),
+            "tick {} out of range",
+            self.tick
+        );
+        ensure!(self.liquidity > 0, "no liquidity in range");
+        Ok(())
+    }
+}
+
+/// Fetch the current state of the v3 pool at `pool` from its `slot0()` and
+/// `liquidity()`, failing if it's not a state a live pool can be in.
+pub async fn fetch_v3_state<P>(provider: Arc<P>, pool: Address) -> Result<V3PoolState>
+where
+    P: Provider,
+{
+    let contract = IUniswapV3Pool::new(pool, provider);
+    let slot0 = contract
+        .slot0()
+        .call()
+        .await
+        .with_context(|| format!("failed to fetch slot0 of {pool}"))?;
+    let liquidity = contract
+        .liquidity()
+        .call()
+        .await
+        .with_context(|| format!("failed to fetch liquidity of {pool}"))?;
+    let state = V3PoolState {
+        sqrt_price_x96: slot0.sqrtPriceX96,
+        tick: slot0.tick.as_i32(),
+        liquidity,
+    };
+    state
+        .validate()
+        .with_context(|| format!("unexpected state for v3 pool {pool}"))?;
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_impossible_pool_states() {
+        let state = V3PoolState {
+            // a price of 1
+            sqrt_price_x96: U160::from(1u128 << 96),
+            tick: 0,
+            liquidity: 1_000,
+        };
+        assert!(state.validate().is_ok());
+
+        assert!(
+            V3PoolState {
+                sqrt_price_x96: U160::ZERO,
+                ..state
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            V3PoolState {
+                tick: MAX_TICK + 1,
+                ..state
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            V3PoolState {
+                liquidity: 0,
+                ..state
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
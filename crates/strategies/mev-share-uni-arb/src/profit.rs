@@ -0,0 +1,224 @@
+use std::time::{Duration, Instant};
+
+use alloy::primitives::{U256, aliases::U160};
+use tracing::info;
+
+/// How often the running profit total is logged.
+const PNL_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 2^96, the fixed point scale of uniswap v3 sqrt prices.
+const Q96: f64 = 79_228_162_514_264_337_593_543_950_336.0;
+
+/// A rough estimate of the profit of a single arb, in wei.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProfitEstimate {
+    /// Weth received from the v2 leg minus weth spent on the v3 leg.
+    pub gross: f64,
+    /// Upper bound on the gas cost, from the gas limit and bid gas price.
+    pub gas_cost: f64,
+    /// Share of the gross profit paid to the block builder's coinbase.
+    pub coinbase_payment: f64,
+    /// Part of `coinbase_payment` that MEV-Share refunds to the user whose
+    /// transaction was backrun, rather than the builder keeping it.
+    pub refund: f64,
+}
+
+impl ProfitEstimate {
+    /// Profit left after paying for gas and the builder. The user refund is
+    /// paid out of the coinbase payment, so it isn't deducted again.
+    pub fn net(&self) -> f64 {
+        self.gross - self.gas_cost - self.coinbase_payment
+    }
+}
+
+/// The part of `coinbase_payment` MEV-Share refunds to the backrun user when
+/// it refunds `refund_percent` percent of the payment.
+pub fn user_refund(coinbase_payment: f64, refund_percent: u64) -> f64 {
+    coinbase_payment.max(0.0) * refund_percent.min(100) as f64 / 100.0
+}
+
+/// Highest share of profit the arb contract can pay to the builder, in
+/// percent. The contract requires some profit to remain, so paying 100%
+/// always reverts.
+pub const MAX_COINBASE_PERCENTAGE: u64 = 99;
+
+/// The `percentageToPayToCoinbase` for an arb: the larger of `bid_percentage`
+/// and the percentage of `gross_profit` needed to pay at least
+/// `min_payment_wei`, raised so that the builder still gets that much after
+/// MEV-Share refunds `refund_percent` percent of the payment to the backrun
+/// user, and capped at [MAX_COINBASE_PERCENTAGE]. Without a positive profit
+/// estimate the floor can't be converted, so only `bid_percentage` applies.
+pub fn coinbase_percentage(
+    bid_percentage: u64,
+    min_payment_wei: U256,
+    refund_percent: u64,
+    gross_profit: Option<f64>,
+) -> U256 {
+    let floor = match gross_profit {
+        Some(gross) if gross > 0.0 && !min_payment_wei.is_zero() => {
+            (f64::from(min_payment_wei) * 100.0 / gross).ceil() as u64
+        }
+        _ => 0,
+    };
+    let percentage = match (bid_percentage.max(floor), 100 - refund_percent.min(100)) {
+        (0, _) => 0,
+        (_, 0) => MAX_COINBASE_PERCENTAGE,
+        (percentage, kept) => (percentage * 100).div_ceil(kept),
+    };
+    U256::from(percentage.min(MAX_COINBASE_PERCENTAGE))
+}
+
+/// Output of a uniswap v2 swap, mirroring `getAmountOut` in the arb contract.
+pub fn v2_amount_out(amount_in: f64, reserve_in: f64, reserve_out: f64) -> f64 {
+    let amount_in_with_fee = amount_in * 997.0;
+    amount_in_with_fee * reserve_out / (reserve_in * 1000.0 + amount_in_with_fee)
+}
+
+/// Estimate the gross profit of buying the non-weth token with `size` weth on
+/// v3 at `sqrt_price_x96`, and selling it on v2. This ignores v3 fees and price
+/// impact, so it overestimates larger sizes. Returns `None` if the price is
+/// unknown.
+pub fn estimate_gross_profit(
+    size: U256,
+    sqrt_price_x96: U160,
+    reserves: (U256, U256),
+    is_weth_token0: bool,
+) -> Option<f64> {
+    let sqrt_price = f64::from(sqrt_price_x96) / Q96;
+    // price of token0 denominated in token1
+    let price = sqrt_price * sqrt_price;
+    if price <= 0.0 || !price.is_finite() {
+        return None;
+    }
+
+    let size = f64::from(size);
+    let (reserve0, reserve1) = (f64::from(reserves.0), f64::from(reserves.1));
+    let weth_out = if is_weth_token0 {
+        v2_amount_out(size * price, reserve1, reserve0)
+    } else {
+        v2_amount_out(size / price, reserve0, reserve1)
+    };
+    Some(weth_out - size)
+}
+
+/// Running total of the estimated profit of the opportunities we've bid on.
+#[derive(Debug, Clone)]
+pub struct PnlTracker {
+    opportunities: u64,
+    total_profit: f64,
+    last_logged: Instant,
+}
+
+impl PnlTracker {
+    pub fn new() -> Self {
+        Self {
+            opportunities: 0,
+            total_profit: 0.0,
+            last_logged: Instant::now(),
+        }
+    }
+
+    /// Record the estimated profit of an opportunity, logging the running
+    /// total if it hasn't been logged recently.
+    pub fn record(&mut self, profit: f64) {
+        self.opportunities += 1;
+        self.total_profit += profit;
+        if self.last_logged.elapsed() >= PNL_LOG_INTERVAL {
+            info!(
+                opportunities = self.opportunities,
+                total_profit = self.total_profit as i128,
+                "estimated pnl"
+            );
+            self.last_logged = Instant::now();
+        }
+    }
+}
+
+impl Default for PnlTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coinbase_percentage_takes_larger_bid() {
+        let gross = Some(1_000.0);
+        assert_eq!(
+            coinbase_percentage(10, U256::ZERO, 0, gross),
+            U256::from(10)
+        );
+        // 250 wei of 1000 is 25%, above the 10% bid.
+        assert_eq!(
+            coinbase_percentage(10, U256::from(250), 0, gross),
+            U256::from(25)
+        );
+        assert_eq!(
+            coinbase_percentage(10, U256::from(251), 0, gross),
+            U256::from(26)
+        );
+        assert_eq!(
+            coinbase_percentage(10, U256::from(5_000), 0, gross),
+            U256::from(99)
+        );
+        assert_eq!(
+            coinbase_percentage(10, U256::from(250), 0, None),
+            U256::from(10)
+        );
+    }
+
+    #[test]
+    fn user_refund_comes_out_of_coinbase_payment() {
+        // The builder only keeps half the payment, so the bid doubles.
+        let gross = Some(1_000.0);
+        assert_eq!(
+            coinbase_percentage(10, U256::from(250), 50, gross),
+            U256::from(50)
+        );
+        assert_eq!(
+            coinbase_percentage(10, U256::ZERO, 90, gross),
+            U256::from(99)
+        );
+        assert_eq!(
+            coinbase_percentage(10, U256::ZERO, 100, gross),
+            U256::from(99)
+        );
+        assert_eq!(coinbase_percentage(0, U256::ZERO, 100, gross), U256::ZERO);
+
+        let refund = user_refund(500.0, 50);
+        assert_eq!(refund, 250.0);
+        let estimate = ProfitEstimate {
+            gross: 1_000.0,
+            gas_cost: 50.0,
+            coinbase_payment: 500.0,
+            refund,
+        };
+        assert_eq!(estimate.net(), 450.0);
+        assert_eq!(user_refund(-10.0, 50), 0.0);
+    }
+
+    #[test]
+    fn estimates_gross_profit_from_price_gap() {
+        // 1 token0 = 4 token1 on both v3 and v2.
+        let sqrt_price_x96 = U160::from(2u64) << 96;
+        let reserves = (U256::from(1e24 as u128), U256::from(4e24 as u128));
+        let gross = estimate_gross_profit(U256::from(1e18 as u128), sqrt_price_x96, reserves, true)
+            .unwrap();
+        assert!(gross < 0.0);
+
+        // v3 price of token1 in weth is half of v2's, so buying on v3 is profitable.
+        let sqrt_price_x96 = U160::from(2u64) << 96;
+        let reserves = (U256::from(2e24 as u128), U256::from(4e24 as u128));
+        let gross = estimate_gross_profit(U256::from(1e18 as u128), sqrt_price_x96, reserves, true)
+            .unwrap();
+        assert!(gross > 0.0);
+
+        assert_eq!(
+            estimate_gross_profit(U256::from(1u64), U160::ZERO, reserves, false),
+            None
+        );
+    }
+}
@@ -1,33 +1,411 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
-use alloy::rpc::types::mev::{BundleItem, Inclusion, MevSendBundle, Privacy, ProtocolVersion};
+use alloy::providers::ext::MevApi;
+use alloy::rpc::types::mev::{
+    BundleItem, EthCallBundle, Inclusion, MevSendBundle, Privacy, PrivacyHint, ProtocolVersion,
+    RefundConfig, Validity,
+};
 use alloy::{
-    eips::Encodable2718,
+    eips::{BlockNumberOrTag, Encodable2718},
     network::{Ethereum, NetworkWallet, TransactionBuilder},
     primitives::Bytes,
-    primitives::{Address, B256, U256 as AlloyU256},
+    primitives::{Address, B256, Log, U256 as AlloyU256, address, aliases::U160},
     providers::Provider,
+    signers::Signer,
+    sol_types::SolEvent,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
+use artemis_core::collectors::builder_stats_collector::BuilderBlockStats;
+use artemis_core::executors::mev_share_executor::SubmittedBundle;
+use artemis_core::signer::SharedSigner;
 use artemis_core::types::Strategy;
+use artemis_core::util::retry;
 use async_trait::async_trait;
-use tracing::info;
+use futures::StreamExt;
+use futures::future::join_all;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::{info, warn};
 
-use crate::types::V2V3PoolRecord;
+use crate::builder::MevShareUniArbBuilder;
+use crate::pool::fetch_v3_state;
+use crate::profit::{
+    PnlTracker, ProfitEstimate, coinbase_percentage, estimate_gross_profit, user_refund,
+};
+use crate::types::{BundleSubmission, OpportunityDetected, V2V3PoolRecord};
 
 use super::types::{Action, Event};
 
-use mev_share_bindings::blind_arb;
+use mev_share_bindings::{
+    blind_arb, i_uniswap_v2_pair::IUniswapV2Pair, i_uniswap_v3_pool::IUniswapV3Pool, iweth::IWETH,
+};
+
+/// Mainnet WETH, the default wrapped native token the arb contract trades
+/// against.
+pub const WETH: Address = address!("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+
+/// On-chain state of the deployed arb contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractStatus {
+    /// Owner of the contract, the only account allowed to execute arbs.
+    pub owner: Address,
+    /// WETH held by the contract, used to fund arbs.
+    pub weth_balance: AlloyU256,
+    /// ETH held by the contract.
+    pub eth_balance: AlloyU256,
+}
+
+/// Per-opportunity parameters of the arb transaction.
+#[derive(Debug, Clone, Copy)]
+struct ArbTxParams {
+    nonce: u64,
+    chain_id: u64,
+    gas_price: u128,
+    payment_percentage: AlloyU256,
+}
 
 /// Information about an uniswap v2 pool.
 #[derive(Debug, Clone)]
 pub struct V2PoolInfo {
     /// Address of the v2 pool.
     pub v2_pool: Address,
-    /// Whether the pool has weth as token0.
+    /// Whether the pool has the base token as token0.
     pub is_weth_token0: bool,
+    /// Token the arb starts and ends in, e.g. a stablecoin, or `None` for the
+    /// wrapped native token. Sizes are in units of this token.
+    pub base_token: Option<Address>,
+}
+
+impl V2PoolInfo {
+    /// Whether a v3 swap in the given direction can be backrun. The arb contract
+    /// always buys the non-weth token on v3 and sells it on v2, so this is only
+    /// profitable when the victim sold the non-weth token into the v3 pool.
+    pub fn can_backrun(&self, zero_for_one: bool) -> bool {
+        zero_for_one != self.is_weth_token0
+    }
+}
+
+/// Decode a uniswap v3 `Swap` log. Returns `None` if the log is not a swap.
+pub fn decode_swap(log: &Log) -> Option<IUniswapV3Pool::Swap> {
+    IUniswapV3Pool::Swap::decode_log_data(&log.data).ok()
+}
+
+/// Direction of a uniswap v3 swap: `true` if the pool received token0 (a
+/// token0 -> token1 swap), and `false` if it received token1.
+pub fn swap_direction(swap: &IUniswapV3Pool::Swap) -> Option<bool> {
+    if swap.amount0.is_positive() {
+        Some(true)
+    } else if swap.amount1.is_positive() {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Find the first v3 swap in `logs` on a pool accepted by `is_known`, along
+/// with its direction. Every log is scanned, since the swap is often preceded
+/// by token transfers.
+pub fn find_swap<'a>(
+    logs: impl IntoIterator<Item = &'a Log>,
+    is_known: impl Fn(&Address) -> bool,
+) -> Option<(Address, IUniswapV3Pool::Swap, bool)> {
+    logs.into_iter().find_map(|log| {
+        if !is_known(&log.address) {
+            return None;
+        }
+        let swap = decode_swap(log)?;
+        let zero_for_one = swap_direction(&swap)?;
+        Some((log.address, swap, zero_for_one))
+    })
+}
+
+/// Default smallest backrun size, in wei.
+pub const DEFAULT_MIN_SIZE: u128 = 100_000;
+/// Default largest backrun size, in wei (1 ETH).
+pub const DEFAULT_MAX_SIZE: u128 = 1_000_000_000_000_000_000;
+/// Default number of geometrically spaced sizes between the min and max.
+pub const DEFAULT_SIZE_STEPS: usize = 14;
+/// Default offset from the current block of the first block a bundle targets.
+pub const DEFAULT_MIN_BLOCK_OFFSET: u64 = 1;
+/// Default offset from the current block of the last block a bundle targets.
+pub const DEFAULT_MAX_BLOCK_OFFSET: u64 = 2;
+/// Default builders MEV-Share may share our bundles with.
+pub const DEFAULT_BUILDERS: &[&str] = &[
+    "flashbots",
+    "Titan",
+    "rsync",
+    "beaverbuild.org",
+    "builder0x69",
+    "Quasar",
+];
+/// Default highest gas price the arb is signed at, in wei (1000 gwei).
+pub const DEFAULT_MAX_GAS_PRICE_WEI: u128 = 1_000_000_000_000;
+/// Default number of recent blocks builder win rates are computed over.
+pub const DEFAULT_BUILDER_WINDOW: usize = 100;
+/// Default number of sizes signed and simulated at once per opportunity.
+pub const DEFAULT_SIGN_CONCURRENCY: usize = 8;
+
+/// Number of pools checked against chain state at once when syncing.
+const POOL_CHECK_CONCURRENCY: usize = 16;
+/// Gas limit of the arb transaction.
+const ARB_GAS_LIMIT: u64 = 400_000;
+/// Attempts made at each provider call before giving up on an opportunity.
+const PROVIDER_ATTEMPTS: usize = 3;
+/// Delay between provider call attempts.
+const PROVIDER_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// How long the strategy holds off on a pool after submitting bundles for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Cooldown {
+    /// Submit for every event.
+    #[default]
+    None,
+    /// Suppress submissions for the pool for a fixed duration.
+    For(Duration),
+    /// Suppress submissions for the pool until the next block.
+    UntilNextBlock,
+}
+
+impl Cooldown {
+    /// Whether a pool last submitted for at `submitted_at` in block
+    /// `submitted_block` is still cooling down in block `block`.
+    pub fn is_active(&self, submitted_at: Instant, submitted_block: u64, block: u64) -> bool {
+        match self {
+            Cooldown::None => false,
+            Cooldown::For(duration) => submitted_at.elapsed() < *duration,
+            Cooldown::UntilNextBlock => block <= submitted_block,
+        }
+    }
+}
+
+/// Which blocks a bundle targets, relative to the block an opportunity is
+/// seen in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockTarget {
+    /// Only the next block, so an arb either lands right away or not at all.
+    NextBlockOnly,
+    /// Every block from `block + min` through `block + max`.
+    Window { min: u64, max: u64 },
+}
+
+impl Default for BlockTarget {
+    fn default() -> Self {
+        BlockTarget::Window {
+            min: DEFAULT_MIN_BLOCK_OFFSET,
+            max: DEFAULT_MAX_BLOCK_OFFSET,
+        }
+    }
+}
+
+impl BlockTarget {
+    /// Offsets from the current block of the first and last block targeted.
+    pub fn offsets(&self) -> (u64, u64) {
+        match *self {
+            BlockTarget::NextBlockOnly => (1, 1),
+            BlockTarget::Window { min, max } => (min, max.max(min)),
+        }
+    }
+}
+
+/// Pools the strategy is allowed to backrun, adjustable at runtime through a
+/// shared handle from [MevShareUniArb::pool_filter].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolFilter {
+    /// Pools that are never backrun.
+    blocked: HashSet<Address>,
+    /// When set, only these pools are backrun.
+    allowed: Option<HashSet<Address>>,
+}
+
+impl PoolFilter {
+    /// Whether the v3 pool `pool` may be backrun.
+    pub fn permits(&self, pool: &Address) -> bool {
+        !self.blocked.contains(pool)
+            && self
+                .allowed
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(pool))
+    }
+
+    /// Stop backrunning `pool`, returning whether it wasn't already blocked.
+    pub fn block(&mut self, pool: Address) -> bool {
+        self.blocked.insert(pool)
+    }
+
+    /// Resume backrunning `pool`, returning whether it was blocked.
+    pub fn unblock(&mut self, pool: &Address) -> bool {
+        self.blocked.remove(pool)
+    }
+
+    /// Only backrun the given pools, or every pool if `None`.
+    pub fn set_allowlist(&mut self, allowed: Option<HashSet<Address>>) {
+        self.allowed = allowed;
+    }
+}
+
+/// Percentage of the arb profit bid to the builder as a function of the size
+/// in wei, set with [MevShareUniArb::with_bid_schedule].
+#[derive(Clone)]
+pub struct BidSchedule(Arc<dyn Fn(AlloyU256) -> u64 + Send + Sync>);
+
+impl BidSchedule {
+    pub fn new<F>(schedule: F) -> Self
+    where
+        F: Fn(AlloyU256) -> u64 + Send + Sync + 'static,
+    {
+        Self(Arc::new(schedule))
+    }
+}
+
+impl std::fmt::Debug for BidSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BidSchedule")
+    }
+}
+
+/// Recent block wins per builder, used to only share bundles with builders
+/// that have won a share of recent blocks. Fed from
+/// [BuilderBlockStats], e.g. from a `BuilderStatsCollector`, through a shared
+/// handle passed to [MevShareUniArb::with_builder_weights].
+///
+/// Relays identify builders by BLS public key while MEV-Share names them, so
+/// only wins by pubkeys registered with [with_builder](Self::with_builder)
+/// are attributed to a builder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuilderWeights {
+    /// MEV-Share builder name by builder pubkey.
+    names: HashMap<String, String>,
+    /// Winning builder of each recent block, oldest first, or `None` if it
+    /// isn't a known builder.
+    recent: VecDeque<Option<String>>,
+    window: usize,
+    min_win_rate: f64,
+}
+
+impl Default for BuilderWeights {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUILDER_WINDOW, 0.0)
+    }
+}
+
+impl BuilderWeights {
+    /// Track wins over the last `window` blocks, dropping builders that won
+    /// less than `min_win_rate` (between 0 and 1) of them.
+    pub fn new(window: usize, min_win_rate: f64) -> Self {
+        Self {
+            names: HashMap::new(),
+            recent: VecDeque::new(),
+            window: window.max(1),
+            min_win_rate,
+        }
+    }
+
+    /// Attribute blocks won by `pubkey` to the builder named `name`.
+    pub fn with_builder(mut self, pubkey: impl Into<String>, name: impl Into<String>) -> Self {
+        self.names.insert(pubkey.into(), name.into());
+        self
+    }
+
+    /// Record the winner of a block.
+    pub fn record(&mut self, stats: &BuilderBlockStats) {
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent
+            .push_back(self.names.get(&stats.builder_pubkey).cloned());
+    }
+
+    /// Share of the recent blocks won by the builder named `name`.
+    pub fn win_rate(&self, name: &str) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        let wins = self
+            .recent
+            .iter()
+            .filter(|winner| winner.as_deref() == Some(name))
+            .count();
+        wins as f64 / self.recent.len() as f64
+    }
+
+    /// The `builders` that won at least the minimum rate of recent blocks,
+    /// most winning first. Every builder is kept, in order, until a block has
+    /// been recorded, or if none of them qualifies.
+    pub fn select(&self, builders: &[String]) -> Vec<String> {
+        if self.recent.is_empty() {
+            return builders.to_vec();
+        }
+        let mut selected: Vec<(f64, &String)> = builders
+            .iter()
+            .map(|builder| (self.win_rate(builder), builder))
+            .filter(|&(rate, _)| rate > 0.0 && rate >= self.min_win_rate)
+            .collect();
+        if selected.is_empty() {
+            return builders.to_vec();
+        }
+        selected.sort_by(|a, b| b.0.total_cmp(&a.0));
+        selected
+            .into_iter()
+            .map(|(_, builder)| builder.clone())
+            .collect()
+    }
+}
+
+/// Bundles the relay accepted for the latest opportunity on each pool, so they
+/// can be cancelled once a newer opportunity on the pool supersedes them.
+#[derive(Debug, Default)]
+pub struct PendingBundles {
+    /// Maps uni v3 pool address to the victim tx hash of its latest
+    /// opportunity, the last block its bundles target, and their bundle hashes.
+    pools: HashMap<Address, (B256, u64, Vec<B256>)>,
+}
+
+impl PendingBundles {
+    /// Track the opportunity on `pool` backrunning `victim`, whose bundles
+    /// target blocks `block` through `max_block`. Returns the bundle hashes of
+    /// the previous opportunity on the pool that could still land by then.
+    pub fn supersede(
+        &mut self,
+        pool: Address,
+        victim: B256,
+        block: u64,
+        max_block: u64,
+    ) -> Vec<B256> {
+        let latest = self
+            .pools
+            .entry(pool)
+            .or_insert((victim, max_block, vec![]));
+        if latest.0 == victim {
+            latest.1 = latest.1.max(max_block);
+            return vec![];
+        }
+        let (_, previous_max_block, hashes) =
+            std::mem::replace(latest, (victim, max_block, vec![]));
+        match previous_max_block >= block {
+            true => hashes,
+            false => vec![],
+        }
+    }
+
+    /// Record the bundle hash the relay assigned a bundle backrunning
+    /// `victim`. Returns it back if that opportunity was already superseded,
+    /// for it to be cancelled right away.
+    pub fn record(&mut self, victim: B256, bundle_hash: B256) -> Option<B256> {
+        match self
+            .pools
+            .values_mut()
+            .find(|(latest, ..)| *latest == victim)
+        {
+            Some((_, _, hashes)) => {
+                hashes.push(bundle_hash);
+                None
+            }
+            None => Some(bundle_hash),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,10 +418,92 @@ where
     provider: Arc<P>,
     /// Maps uni v3 pool address to v2 pool information.
     pool_map: HashMap<Address, V2PoolInfo>,
-    /// Wallet used for signing transactions.
-    wallet: W,
+    /// Expected number of pools, used to pre-size `pool_map` when loading.
+    pool_capacity: usize,
+    /// Pool csv loaded on sync, or the bundled pools if unset.
+    pools_path: Option<PathBuf>,
+    /// Wrapped native token the arb contract trades against, WETH by default.
+    wrapped_native: Address,
+    /// Whether to check each pool's `weth_token0` flag on-chain when syncing.
+    verify_pools: bool,
+    /// Whether to check each pool record against chain state when syncing.
+    validate_pools: bool,
+    /// Allowlist and blocklist of v3 pools, shared with whoever manages them.
+    pool_filter: Arc<RwLock<PoolFilter>>,
+    /// Wallets used for signing transactions, used in turn for each
+    /// opportunity. Never empty.
+    wallets: Vec<W>,
+    /// Index into `wallets` of the wallet used for the next opportunity.
+    /// Shared between clones, like the other per-opportunity state.
+    next_wallet: Arc<AtomicUsize>,
     /// BlindArb contract instance.
     arb_contract: blind_arb::BlindArb::BlindArbInstance<Arc<P>>,
+    /// The sizes of the backruns we submit for each opportunity.
+    sizes: Vec<AlloyU256>,
+    /// When set, sizes are instead these percentages of the arb contract's
+    /// WETH balance at opportunity time.
+    balance_percentages: Option<Vec<f64>>,
+    /// Blocks a bundle is valid for, relative to the current block.
+    block_target: BlockTarget,
+    /// Minimum estimated net profit for a size to be submitted. Zero disables
+    /// the check.
+    min_profit_wei: AlloyU256,
+    /// Running total of estimated profit.
+    pnl: Arc<Mutex<PnlTracker>>,
+    /// How long to hold off on a pool after submitting for it.
+    cooldown: Cooldown,
+    /// Maps uni v3 pool address to the time and block we last submitted for it.
+    last_submission: Arc<Mutex<HashMap<Address, (Instant, u64)>>>,
+    /// Receives a record of every submitted bundle, if set.
+    submission_sender: Option<UnboundedSender<BundleSubmission>>,
+    /// Receives a record of every opportunity sized, if set.
+    opportunity_sender: Option<UnboundedSender<OpportunityDetected>>,
+    /// Bundles the relay accepted, read to cancel superseded ones, if set.
+    submitted_bundles: Option<Arc<Mutex<UnboundedReceiver<SubmittedBundle>>>>,
+    /// Accepted bundles of the latest opportunity on each pool.
+    pending_bundles: Arc<Mutex<PendingBundles>>,
+    /// Chain id of the provider, fetched on first use.
+    chain_id: OnceLock<u64>,
+    /// Whether to also submit each arb as a plain Flashbots bundle, when the
+    /// victim's raw transaction is known.
+    eth_bundles: bool,
+    /// Skip opportunities while the latest base fee is above this, in wei.
+    max_base_fee: Option<u64>,
+    /// Skip opportunities if the fetched gas price is above this, in wei.
+    max_gas_price_wei: u128,
+    /// Builders MEV-Share may share our bundles with.
+    builders: Vec<String>,
+    /// Recent builder wins to narrow `builders` down by, if set.
+    builder_weights: Option<Arc<RwLock<BuilderWeights>>>,
+    /// What MEV-Share may reveal about our bundles, or the relay's default if
+    /// unset.
+    privacy_hints: Option<PrivacyHint>,
+    /// Percentage of the arb profit paid to the builder.
+    bid_percentage: u64,
+    /// Bid percentage per size, overriding `bid_percentage` if set.
+    bid_schedule: Option<BidSchedule>,
+    /// Minimum absolute payment to the builder, in wei.
+    min_coinbase_payment_wei: AlloyU256,
+    /// Percent of our coinbase payment that MEV-Share refunds to the user we
+    /// backrun, which the bid is raised by. Unrelated to `refund_percent`.
+    user_refund_percent: u64,
+    /// Percent of any refund owed to our own bundles, when they are backrun,
+    /// that is paid out, if set.
+    refund_percent: Option<u64>,
+    /// Recipient of refunds, defaulting to the wallet that signed the arb.
+    refund_recipient: Option<Address>,
+    /// Recipients splitting refunds by percentage, overriding the single
+    /// refund recipient unless empty.
+    refund_split: Vec<(Address, u8)>,
+    /// Maximum number of sizes signed and simulated at once per opportunity.
+    sign_concurrency: usize,
+    /// Whether to submit only the most profitable size of each opportunity.
+    submit_best_only: bool,
+    /// Maximum number of bundles submitted per opportunity, if set.
+    max_bundles_per_event: Option<usize>,
+    /// Relay and auth signer used to simulate arbs with `eth_callBundle`
+    /// before submitting them, if set.
+    simulator: Option<(Arc<P>, SharedSigner)>,
 }
 
 impl<P, W> MevShareUniArb<P, W>
@@ -57,8 +517,505 @@ where
         Self {
             provider,
             pool_map: HashMap::new(),
-            wallet,
+            pool_capacity: 0,
+            pools_path: None,
+            wrapped_native: WETH,
+            verify_pools: false,
+            validate_pools: false,
+            pool_filter: Arc::default(),
+            wallets: vec![wallet],
+            next_wallet: Arc::default(),
             arb_contract,
+            sizes: geometric_sizes(DEFAULT_MIN_SIZE, DEFAULT_MAX_SIZE, DEFAULT_SIZE_STEPS),
+            balance_percentages: None,
+            block_target: BlockTarget::default(),
+            min_profit_wei: AlloyU256::ZERO,
+            pnl: Arc::default(),
+            cooldown: Cooldown::default(),
+            last_submission: Arc::default(),
+            submission_sender: None,
+            opportunity_sender: None,
+            submitted_bundles: None,
+            pending_bundles: Arc::default(),
+            chain_id: OnceLock::new(),
+            eth_bundles: false,
+            max_base_fee: None,
+            max_gas_price_wei: DEFAULT_MAX_GAS_PRICE_WEI,
+            simulator: None,
+            sign_concurrency: DEFAULT_SIGN_CONCURRENCY,
+            submit_best_only: false,
+            max_bundles_per_event: None,
+            bid_percentage: 0,
+            bid_schedule: None,
+            min_coinbase_payment_wei: AlloyU256::ZERO,
+            user_refund_percent: 0,
+            refund_percent: None,
+            refund_recipient: None,
+            refund_split: Vec::new(),
+            privacy_hints: None,
+            builders: DEFAULT_BUILDERS
+                .iter()
+                .map(|builder| builder.to_string())
+                .collect(),
+            builder_weights: None,
+        }
+    }
+
+    /// Fetch the owner and balances of the arb contract.
+    pub async fn contract_status(&self) -> Result<ContractStatus> {
+        let contract = *self.arb_contract.address();
+        let owner = self
+            .arb_contract
+            .owner()
+            .call()
+            .await
+            .context("failed to fetch arb contract owner")?;
+        let weth_balance = IWETH::new(self.wrapped_native, self.provider.clone())
+            .balanceOf(contract)
+            .call()
+            .await
+            .context("failed to fetch arb contract weth balance")?;
+        let eth_balance = self
+            .provider
+            .get_balance(contract)
+            .await
+            .context("failed to fetch arb contract eth balance")?;
+        Ok(ContractStatus {
+            owner,
+            weth_balance,
+            eth_balance,
+        })
+    }
+
+    /// Start building a strategy with more than the default options, see
+    /// [MevShareUniArbBuilder].
+    pub fn builder(
+        provider: Arc<P>,
+        wallet: W,
+        arb_contract_address: Address,
+    ) -> MevShareUniArbBuilder<P, W> {
+        MevShareUniArbBuilder::new(provider, wallet, arb_contract_address)
+    }
+
+    /// Round-robin across `signers` for each opportunity, so that arbs on
+    /// different pools in the same block don't contend for one nonce. Every
+    /// signer must be allowed to call the arb contract. An empty list keeps
+    /// the current wallet.
+    pub fn with_signers(mut self, signers: Vec<W>) -> Self {
+        if !signers.is_empty() {
+            self.wallets = signers;
+            self.next_wallet = Arc::default();
+        }
+        self
+    }
+
+    /// Ask MEV-Share to pay `percent` percent of any refund owed to our bundles
+    /// to `recipient`, or to the wallet that signed each arb if `None`.
+    pub fn with_refund(mut self, percent: u64, recipient: Option<Address>) -> Self {
+        self.refund_percent = Some(percent.min(100));
+        self.refund_recipient = recipient;
+        self
+    }
+
+    /// Split any refund owed to our bundles across several recipients, each
+    /// taking the given percent. Takes precedence over
+    /// [with_refund](Self::with_refund). Fails if the percentages add up to
+    /// more than 100.
+    pub fn with_refund_split(mut self, split: Vec<(Address, u8)>) -> Result<Self> {
+        check_refund_split(&split)?;
+        self.refund_split = split;
+        Ok(self)
+    }
+
+    /// Pay the builder the larger of `bid_percentage` percent of the arb
+    /// profit and `min_payment_wei`. The contract only takes a percentage, so
+    /// the floor is converted using each size's estimated gross profit, and
+    /// ignored for sizes without an estimate. The payment is raised to cover
+    /// the [user refund](Self::with_user_refund_percent) taken out of it, and
+    /// capped at [MAX_COINBASE_PERCENTAGE](crate::profit::MAX_COINBASE_PERCENTAGE).
+    pub fn with_coinbase_payment(
+        mut self,
+        bid_percentage: u64,
+        min_payment_wei: AlloyU256,
+    ) -> Self {
+        self.bid_percentage = bid_percentage;
+        self.min_coinbase_payment_wei = min_payment_wei;
+        self
+    }
+
+    /// Account for MEV-Share refunding `percent` percent of each arb's coinbase
+    /// payment to the user that was backrun. The payment is raised so the
+    /// builder still gets the configured bid, and the profit threshold and
+    /// sizing apply to what we keep after the larger payment. This is the
+    /// refund paid out of our bids, unlike [with_refund](Self::with_refund),
+    /// which directs refunds owed to our own bundles.
+    pub fn with_user_refund_percent(mut self, percent: u64) -> Self {
+        self.user_refund_percent = percent.min(100);
+        self
+    }
+
+    /// Bid `schedule(size)` percent of the arb profit for each size instead
+    /// of a fixed percentage, e.g. to bid more on larger, more profitable
+    /// sizes. The floor and cap of
+    /// [with_coinbase_payment](Self::with_coinbase_payment) still apply.
+    pub fn with_bid_schedule(mut self, schedule: BidSchedule) -> Self {
+        self.bid_schedule = Some(schedule);
+        self
+    }
+
+    /// Load pools from the csv at `path` at runtime instead of the
+    /// [BUNDLED_POOLS], e.g. to run one instance of the strategy per
+    /// liquidity tier.
+    pub fn with_pools_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.pools_path = Some(path.into());
+        self
+    }
+
+    /// Pre-size the pool map for about `capacity` pools, so loading a large
+    /// pool file doesn't repeatedly rehash it.
+    pub fn with_pool_capacity(mut self, capacity: usize) -> Self {
+        self.pool_capacity = capacity;
+        self
+    }
+
+    /// Sign and simulate at most `concurrency` sizes of an opportunity at once.
+    pub fn with_sign_concurrency(mut self, concurrency: usize) -> Self {
+        self.sign_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Submit a single bundle per opportunity, for the size with the highest
+    /// net profit, instead of one per size. Profit is taken from simulation
+    /// when the arb was simulated, see
+    /// [with_bundle_simulation](Self::with_bundle_simulation), and paid the
+    /// builder a percentage of it, and estimated otherwise.
+    pub fn with_submit_best_only(mut self, submit_best_only: bool) -> Self {
+        self.submit_best_only = submit_best_only;
+        self
+    }
+
+    /// Submit at most `max` bundles per opportunity, keeping the sizes with
+    /// the highest net profit, measured as for
+    /// [with_submit_best_only](Self::with_submit_best_only). Sizes without a
+    /// profit figure are kept last, in size order.
+    pub fn with_max_bundles_per_event(mut self, max: usize) -> Self {
+        self.max_bundles_per_event = Some(max);
+        self
+    }
+
+    /// Trade against `wrapped_native` instead of mainnet WETH, e.g. WMATIC or
+    /// WBNB on other chains. The arb contract must be deployed with the same
+    /// token, and the pool file's `weth_token0` column then refers to it.
+    pub fn with_wrapped_native(mut self, wrapped_native: Address) -> Self {
+        self.wrapped_native = wrapped_native;
+        self
+    }
+
+    /// Check every pool's `weth_token0` flag against its v2 pair's `token0()`
+    /// when syncing, correcting and warning about mismatches, and dropping
+    /// pairs that don't hold the base token at all. This takes a call or two
+    /// per pool.
+    pub fn with_pool_verification(mut self, verify_pools: bool) -> Self {
+        self.verify_pools = verify_pools;
+        self
+    }
+
+    /// Check every pool record against chain state when syncing, as with
+    /// [with_pool_verification](Self::with_pool_verification), and also drop
+    /// records whose pools aren't contracts or whose v2 pair has no reserves.
+    /// This takes several calls per pool.
+    pub fn with_pool_validation(mut self, validate_pools: bool) -> Self {
+        self.validate_pools = validate_pools;
+        self
+    }
+
+    /// Use a shared [PoolFilter] to decide which pools may be backrun.
+    pub fn with_pool_filter(mut self, pool_filter: Arc<RwLock<PoolFilter>>) -> Self {
+        self.pool_filter = pool_filter;
+        self
+    }
+
+    /// A handle to the strategy's [PoolFilter], to block or allow pools while
+    /// it runs.
+    pub fn pool_filter(&self) -> Arc<RwLock<PoolFilter>> {
+        self.pool_filter.clone()
+    }
+
+    /// A snapshot of the loaded pools, as v3 pool address and v2 pool info,
+    /// sorted by v3 pool address.
+    pub fn pools(&self) -> Vec<(Address, V2PoolInfo)> {
+        let mut pools: Vec<_> = self
+            .pool_map
+            .iter()
+            .map(|(v3_pool, info)| (*v3_pool, info.clone()))
+            .collect();
+        pools.sort_by_key(|(v3_pool, _)| *v3_pool);
+        pools
+    }
+
+    /// Simulate each signed arb behind its victim with `eth_callBundle` on
+    /// `relay`, signed by `auth_signer`, and only submit sizes whose
+    /// simulation succeeds. The arb contract reverts unless it's profitable
+    /// after paying the builder, so a successful simulation means a positive
+    /// simulated profit. The builder payment is then re-bid from the simulated
+    /// profit rather than the reserve estimate.
+    ///
+    /// `eth_callBundle` needs the victim's raw transaction, while MEV-Share
+    /// only shares its hash, so arbs are only simulated when the victim can
+    /// be fetched from the provider, i.e. it's also in the public mempool.
+    /// Other arbs are submitted unsimulated.
+    pub fn with_bundle_simulation(
+        mut self,
+        relay: Arc<P>,
+        auth_signer: impl Signer + Send + Sync + 'static,
+    ) -> Self {
+        self.simulator = Some((relay, SharedSigner::new(auth_signer)));
+        self
+    }
+
+    /// Set the builders MEV-Share may share our bundles with.
+    pub fn with_builders(mut self, builders: Vec<String>) -> Self {
+        self.builders = builders;
+        self
+    }
+
+    /// Only share bundles with the builders winning enough recent blocks
+    /// according to `weights`, out of those set with
+    /// [with_builders](Self::with_builders).
+    pub fn with_builder_weights(mut self, weights: Arc<RwLock<BuilderWeights>>) -> Self {
+        self.builder_weights = Some(weights);
+        self
+    }
+
+    /// Only let MEV-Share reveal the given hints about our bundles, e.g.
+    /// `PrivacyHint::default().with_hash()` to share nothing but the bundle
+    /// hash.
+    pub fn with_privacy_hints(mut self, hints: PrivacyHint) -> Self {
+        self.privacy_hints = Some(hints);
+        self
+    }
+
+    /// Skip opportunities while the latest block's base fee is above
+    /// `max_base_fee` wei, when the fixed gas cost of the arb likely outweighs
+    /// its profit.
+    pub fn with_max_base_fee(mut self, max_base_fee: u64) -> Self {
+        self.max_base_fee = Some(max_base_fee);
+        self
+    }
+
+    /// Refuse to sign arbs if the provider reports a gas price above
+    /// `max_gas_price_wei`, which most likely means bad RPC data rather than
+    /// a market worth paying. Defaults to [DEFAULT_MAX_GAS_PRICE_WEI].
+    pub fn with_max_gas_price(mut self, max_gas_price_wei: u128) -> Self {
+        self.max_gas_price_wei = max_gas_price_wei;
+        self
+    }
+
+    /// Also submit each arb as a plain bundle to builders directly, see
+    /// [artemis_core::mevshare::Event::backrun_bundle]. Only possible when
+    /// the victim is also in the public mempool, so its raw transaction can
+    /// be fetched from the provider.
+    pub fn with_eth_bundles(mut self, eth_bundles: bool) -> Self {
+        self.eth_bundles = eth_bundles;
+        self
+    }
+
+    /// Send a [BundleSubmission] for every bundle generated to `sender`.
+    /// Records are dropped once its receiver is gone.
+    pub fn with_submission_sender(mut self, sender: UnboundedSender<BundleSubmission>) -> Self {
+        self.submission_sender = Some(sender);
+        self
+    }
+
+    /// Send an [OpportunityDetected] to `sender` for every opportunity sized,
+    /// before any bundle is built, including those no size is profitable for.
+    /// Records are dropped once its receiver is gone.
+    pub fn with_opportunity_sender(mut self, sender: UnboundedSender<OpportunityDetected>) -> Self {
+        self.opportunity_sender = Some(sender);
+        self
+    }
+
+    /// Cancel a pool's bundles, while they could still land, once bundles for
+    /// a newer opportunity on the pool are submitted. The bundle hashes needed
+    /// to cancel them are read from `receiver`, e.g. as fed by
+    /// `MevshareExecutor::with_submitted_sender`.
+    pub fn with_bundle_cancellation(
+        mut self,
+        receiver: UnboundedReceiver<SubmittedBundle>,
+    ) -> Self {
+        self.submitted_bundles = Some(Arc::new(Mutex::new(receiver)));
+        self
+    }
+
+    /// Hold off on a pool after submitting bundles for it, to avoid spending
+    /// relay quota on the same opportunity.
+    pub fn with_cooldown(mut self, cooldown: Cooldown) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Only submit sizes whose estimated net profit is at least `min_profit_wei`.
+    /// If no size clears the bar, nothing is submitted. Profit is only
+    /// estimated for wrapped native arbs, so pools with another base token
+    /// aren't held to the threshold.
+    pub fn with_min_profit(mut self, min_profit_wei: AlloyU256) -> Self {
+        self.min_profit_wei = min_profit_wei;
+        self
+    }
+
+    /// Set the inclusion window of submitted bundles, as offsets from the
+    /// current block. A bundle is valid from `block + min_offset` through
+    /// `block + max_offset`.
+    pub fn with_block_window(mut self, min_offset: u64, max_offset: u64) -> Self {
+        self.block_target = BlockTarget::Window {
+            min: min_offset,
+            max: max_offset.max(min_offset),
+        };
+        self
+    }
+
+    /// Set the blocks submitted bundles target, e.g.
+    /// [BlockTarget::NextBlockOnly] for arbs that only exist in the next
+    /// block.
+    pub fn with_block_target(mut self, target: BlockTarget) -> Self {
+        self.block_target = target;
+        self
+    }
+
+    /// Size backruns as percentages of the arb contract's WETH balance,
+    /// fetched for each opportunity, so we never bid more than the contract
+    /// can fund. Percentages are clamped to 100. Falls back to the absolute
+    /// size range if the balance can't be fetched.
+    pub fn with_balance_percentages(mut self, percentages: Vec<f64>) -> Self {
+        self.balance_percentages = Some(percentages);
+        self
+    }
+
+    /// Set the range of backrun sizes. `steps` sizes are spaced geometrically
+    /// between `min_size` and `max_size` (inclusive).
+    pub fn with_size_range(mut self, min_size: u128, max_size: u128, steps: usize) -> Self {
+        self.sizes = geometric_sizes(min_size, max_size, steps);
+        self
+    }
+}
+
+/// The bundle validity paying `percent` percent of refunds to `recipient`,
+/// falling back to `signer` when no recipient is given. `None` without a
+/// refund percentage.
+pub fn refund_validity(
+    percent: Option<u64>,
+    recipient: Option<Address>,
+    signer: Address,
+) -> Option<Validity> {
+    let percent = percent?;
+    Some(Validity {
+        refund: None,
+        refund_config: Some(vec![RefundConfig {
+            address: recipient.unwrap_or(signer),
+            percent,
+        }]),
+    })
+}
+
+/// Fails if the percentages of a refund split add up to more than 100.
+fn check_refund_split(split: &[(Address, u8)]) -> Result<()> {
+    let total: u64 = split.iter().map(|(_, percent)| u64::from(*percent)).sum();
+    anyhow::ensure!(total <= 100, "refund split adds up to {total}%");
+    Ok(())
+}
+
+/// The bundle validity splitting refunds across `split`, or `None` if it is
+/// empty.
+pub fn refund_split_validity(split: &[(Address, u8)]) -> Option<Validity> {
+    if split.is_empty() {
+        return None;
+    }
+    Some(Validity {
+        refund: None,
+        refund_config: Some(
+            split
+                .iter()
+                .map(|&(address, percent)| RefundConfig {
+                    address,
+                    percent: percent.into(),
+                })
+                .collect(),
+        ),
+    })
+}
+
+/// Profit an arb made before paying `coinbase_payment` to the builder as
+/// `payment_percentage` of it, or `None` if nothing was due, which leaves the
+/// profit unknown.
+pub fn simulated_gross_profit(
+    coinbase_payment: AlloyU256,
+    payment_percentage: AlloyU256,
+) -> Option<AlloyU256> {
+    if payment_percentage.is_zero() {
+        return None;
+    }
+    Some(coinbase_payment * AlloyU256::from(100) / payment_percentage)
+}
+
+/// The pool csv bundled with this crate, compiled into the binary so that it
+/// runs without the crate's sources.
+pub const BUNDLED_POOLS: &str = include_str!("../resources/v3_v2_pools.csv");
+
+/// Read `v3_pool -> v2 pool` records from a pool csv into a map pre-sized for
+/// `capacity` pools. Rows are parsed one at a time into a single reused
+/// record, so memory only grows with the map itself.
+pub fn read_pools<R: std::io::Read>(
+    mut reader: csv::Reader<R>,
+    capacity: usize,
+) -> Result<HashMap<Address, V2PoolInfo>> {
+    let headers = reader.headers()?.clone();
+    let mut pools = HashMap::with_capacity(capacity);
+    let mut row = csv::StringRecord::new();
+    while reader.read_record(&mut row)? {
+        let record: V2V3PoolRecord = row
+            .deserialize(Some(&headers))
+            .with_context(|| format!("invalid pool record at {:?}", row.position()))?;
+        pools.insert(
+            record.v3_pool,
+            V2PoolInfo {
+                v2_pool: record.v2_pool,
+                is_weth_token0: record.weth_token0,
+                base_token: record.base_token,
+            },
+        );
+    }
+    Ok(pools)
+}
+
+/// Scale `balance` by each of `percentages`, clamped to between 0 and 100.
+pub fn balance_sizes(balance: AlloyU256, percentages: &[f64]) -> Vec<AlloyU256> {
+    percentages
+        .iter()
+        .map(|percentage| {
+            let bps = (percentage.clamp(0.0, 100.0) * 100.0).round() as u64;
+            balance * AlloyU256::from(bps) / AlloyU256::from(10_000)
+        })
+        .filter(|size| !size.is_zero())
+        .collect()
+}
+
+/// Compute `steps` geometrically spaced sizes from `min_size` to `max_size`
+/// (inclusive). The defaults yield every power of ten from 1e5 to 1e18 wei.
+pub fn geometric_sizes(min_size: u128, max_size: u128, steps: usize) -> Vec<AlloyU256> {
+    let min_size = min_size.max(1);
+    match steps {
+        0 => vec![],
+        1 => vec![AlloyU256::from(min_size)],
+        _ => {
+            // Interpolate in log space so that decade ranges stay exact.
+            let min_log = (min_size as f64).log10();
+            let step = ((max_size.max(min_size) as f64).log10() - min_log) / (steps - 1) as f64;
+            (0..steps)
+                .map(|i| {
+                    let size = 10f64.powf(min_log + step * i as f64).round() as u128;
+                    AlloyU256::from(size)
+                })
+                .collect()
         }
     }
 }
@@ -73,19 +1030,29 @@ where
     /// pool information into memory.
     async fn sync_state(&mut self) -> Result<()> {
         // Read pool information from csv file.
-        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        path.push("resources/v3_v2_pools.csv");
-        let mut reader = csv::Reader::from_path(path)?;
-
-        for record in reader.deserialize() {
-            // Parse records into PoolRecord struct.
-            let record: V2V3PoolRecord = record?;
-            self.pool_map.insert(
-                record.v3_pool,
-                V2PoolInfo {
-                    v2_pool: record.v2_pool,
-                    is_weth_token0: record.weth_token0,
-                },
+        self.pool_map = match &self.pools_path {
+            Some(path) => {
+                let reader = csv::Reader::from_path(path)
+                    .with_context(|| format!("failed to open pool file {path:?}"))?;
+                read_pools(reader, self.pool_capacity)?
+            }
+            None => read_pools(
+                csv::Reader::from_reader(BUNDLED_POOLS.as_bytes()),
+                self.pool_capacity,
+            )?,
+        };
+        if self.verify_pools || self.validate_pools {
+            let mismatches = self.verify_pool_tokens().await;
+            info!(
+                pools = self.pool_map.len(),
+                mismatches, "verified pool tokens"
+            );
+        }
+        if self.validate_pools {
+            let dropped = self.validate_pool_records().await;
+            info!(
+                valid = self.pool_map.len(),
+                dropped, "validated pool records"
             );
         }
 
@@ -96,26 +1063,73 @@ where
     async fn process_event(&mut self, event: Event) -> Vec<Action> {
         match event {
             Event::MEVShareEvent(event) => {
-                info!("Received mev share event: {:?}", event);
-                // skip if event has no logs
-                if event.logs.is_empty() {
-                    return vec![];
-                }
-                let address = event.logs[0].address;
-                // skip if address is not a v3 pool
-                if !self.pool_map.contains_key(&address) {
-                    return vec![];
+                info!("Received mev share event: {}", event);
+                let mut actions = self.record_submitted_bundles();
+                // find the first swap on a permitted v3 pool we have a v2 pool for
+                let Some((address, swap, zero_for_one)) = find_swap(&event.logs, |pool| {
+                    self.pool_map.contains_key(pool) && self.permits_pool(pool)
+                }) else {
+                    return actions;
+                };
+                // skip if the swap moved the price against our arb
+                if !self.pool_map[&address].can_backrun(zero_for_one) {
+                    info!(
+                        "Skipping v3 swap at address {:?} in unprofitable direction",
+                        address
+                    );
+                    return actions;
                 }
                 // if it's a v3 pool we care about, submit bundles
                 info!(
                     "Found a v3 pool match at address {:?}, submitting bundles",
                     address
                 );
-                self.generate_bundles(address, event.hash)
-                    .await
-                    .into_iter()
-                    .map(Action::SubmitBundle)
-                    .collect()
+                let bundles = self
+                    .generate_bundles_with_price(address, event.hash, swap.sqrtPriceX96)
+                    .await;
+                let victim_tx = match self.eth_bundles && !bundles.is_empty() {
+                    true => self.fetch_victim_tx(event.hash).await,
+                    false => None,
+                };
+                let eth_bundles: Vec<_> = match victim_tx {
+                    Some(victim_tx) => bundles
+                        .iter()
+                        .filter_map(|bundle| {
+                            let tx = bundle.bundle_body.iter().find_map(|item| match item {
+                                BundleItem::Tx { tx, .. } => Some(tx.clone()),
+                                _ => None,
+                            })?;
+                            event.backrun_bundle(victim_tx.clone(), tx, bundle.inclusion.block)
+                        })
+                        .collect(),
+                    None => vec![],
+                };
+                if let Some(bundle) = bundles.first()
+                    && self.submitted_bundles.is_some()
+                {
+                    let inclusion = &bundle.inclusion;
+                    let stale = self
+                        .pending_bundles
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .supersede(
+                            address,
+                            event.hash,
+                            inclusion.block,
+                            inclusion.max_block.unwrap_or(inclusion.block),
+                        );
+                    if !stale.is_empty() {
+                        info!(
+                            "Cancelling {} superseded bundles on v3 pool {:?}",
+                            stale.len(),
+                            address
+                        );
+                    }
+                    actions.extend(stale.into_iter().map(Action::CancelBundle));
+                }
+                actions.extend(bundles.into_iter().map(Action::SubmitBundle));
+                actions.extend(eth_bundles.into_iter().map(Action::SubmitEthBundle));
+                actions
             }
         }
     }
@@ -126,55 +1140,581 @@ where
     P: Provider + Send + Sync + 'static,
     W: NetworkWallet<Ethereum> + Clone + Send + Sync + 'static,
 {
+    /// Build and sign the arb transaction for `size` on `v3_address`, exactly
+    /// as [generate_bundles](Self::generate_bundles) would, without submitting
+    /// it. The transaction uses the wallet next in line and its current nonce.
+    pub async fn build_arb_tx(&self, v3_address: Address, size: AlloyU256) -> Result<Bytes> {
+        let wallet = &self.wallets[self.next_wallet.load(Ordering::Relaxed) % self.wallets.len()];
+        let gas_price = self
+            .provider
+            .get_gas_price()
+            .await
+            .context("failed to fetch gas price")?;
+        anyhow::ensure!(
+            gas_price <= self.max_gas_price_wei,
+            "gas price {gas_price} exceeds max {}",
+            self.max_gas_price_wei
+        );
+        let chain_id = match self.chain_id.get() {
+            Some(&id) => id,
+            None => self
+                .provider
+                .get_chain_id()
+                .await
+                .context("failed to fetch chain id")?,
+        };
+        let nonce = self
+            .provider
+            .get_transaction_count(wallet.default_signer_address())
+            .await
+            .context("failed to fetch signer nonce")?;
+        let params = ArbTxParams {
+            nonce,
+            chain_id,
+            gas_price,
+            payment_percentage: self.payment_percentage(size, None),
+        };
+        self.sign_arb_tx(wallet, v3_address, size, params).await
+    }
+
+    /// The `percentageToPayToCoinbase` for `size`, given its estimated
+    /// `gross_profit` if known.
+    fn payment_percentage(&self, size: AlloyU256, gross_profit: Option<f64>) -> AlloyU256 {
+        coinbase_percentage(
+            self.bid_percentage(size),
+            self.min_coinbase_payment_wei,
+            self.user_refund_percent,
+            gross_profit,
+        )
+    }
+
+    /// Percentage of the arb profit bid for `size`, before the payment floor.
+    fn bid_percentage(&self, size: AlloyU256) -> u64 {
+        match &self.bid_schedule {
+            Some(BidSchedule(schedule)) => schedule(size),
+            None => self.bid_percentage,
+        }
+    }
+
+    /// Build and sign the arb transaction for `size` on `v3_address`, returning
+    /// its EIP-2718 encoding.
+    async fn sign_arb_tx(
+        &self,
+        wallet: &W,
+        v3_address: Address,
+        size: AlloyU256,
+        params: ArbTxParams,
+    ) -> Result<Bytes> {
+        let v2_info = self
+            .pool_map
+            .get(&v3_address)
+            .with_context(|| format!("no v2 pool for v3 pool {v3_address}"))?;
+
+        // Construct arb tx based on the base token and whether the v2 pool has
+        // it as token0. Only wrapped native arbs pay the coinbase.
+        let mut tx = match (self.base_token(v2_info), v2_info.is_weth_token0) {
+            (None, true) => self
+                .arb_contract
+                .executeArb__WETH_token0(
+                    v2_info.v2_pool,
+                    v3_address,
+                    size,
+                    params.payment_percentage,
+                )
+                .into_transaction_request(),
+            (None, false) => self
+                .arb_contract
+                .executeArb__WETH_token1(
+                    v2_info.v2_pool,
+                    v3_address,
+                    size,
+                    params.payment_percentage,
+                )
+                .into_transaction_request(),
+            (Some(base_token), true) => self
+                .arb_contract
+                .executeArb__token0(base_token, v2_info.v2_pool, v3_address, size)
+                .into_transaction_request(),
+            (Some(base_token), false) => self
+                .arb_contract
+                .executeArb__token1(base_token, v2_info.v2_pool, v3_address, size)
+                .into_transaction_request(),
+        };
+        tx.set_from(wallet.default_signer_address());
+        tx.set_nonce(params.nonce);
+        tx.set_chain_id(params.chain_id);
+        tx.set_gas_limit(ARB_GAS_LIMIT);
+        tx.set_gas_price(params.gas_price);
+        tx.set_value(AlloyU256::ZERO);
+
+        info!("generated arb tx: {:?}", tx);
+
+        let envelope = tx.build(wallet).await?;
+        Ok(Bytes::from(envelope.encoded_2718()))
+    }
+
+    /// The base token of a pool, or `None` if it's the wrapped native token.
+    fn base_token(&self, info: &V2PoolInfo) -> Option<Address> {
+        info.base_token
+            .filter(|token| *token != self.wrapped_native)
+    }
+
+    /// A copy of every pool record, to check them while the pool map may
+    /// change.
+    fn pool_records(&self) -> Vec<(Address, V2PoolInfo)> {
+        self.pool_map
+            .iter()
+            .map(|(v3_pool, info)| (*v3_pool, info.clone()))
+            .collect()
+    }
+
+    /// Whether the base token of `info` is token0 of its v2 pair on-chain, or
+    /// `None` if the pair doesn't hold it.
+    async fn base_token_is_token0(&self, info: &V2PoolInfo) -> Result<Option<bool>> {
+        let base_token = info.base_token.unwrap_or(self.wrapped_native);
+        let pair = IUniswapV2Pair::new(info.v2_pool, self.provider.clone());
+        if pair.token0().call().await? == base_token {
+            return Ok(Some(true));
+        }
+        if pair.token1().call().await? == base_token {
+            return Ok(Some(false));
+        }
+        Ok(None)
+    }
+
+    /// Check the `weth_token0` flag of every pool against its v2 pair on-chain,
+    /// fixing wrong flags and dropping pairs without the base token. Pools
+    /// that can't be checked are kept as they are. Returns the number of pools
+    /// fixed or dropped.
+    async fn verify_pool_tokens(&mut self) -> usize {
+        let this = &*self;
+        let checked: Vec<_> = futures::stream::iter(this.pool_records())
+            .map(|(v3_pool, info)| async move {
+                let is_weth_token0 = this.base_token_is_token0(&info).await;
+                (v3_pool, info, is_weth_token0)
+            })
+            .buffer_unordered(POOL_CHECK_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut mismatches = 0;
+        for (v3_pool, info, is_weth_token0) in checked {
+            match is_weth_token0 {
+                Ok(Some(is_weth_token0)) if is_weth_token0 != info.is_weth_token0 => {
+                    warn!(
+                        v3_pool = %v3_pool,
+                        v2_pool = %info.v2_pool,
+                        csv = info.is_weth_token0,
+                        on_chain = is_weth_token0,
+                        "weth_token0 flag doesn't match the v2 pair, using the on-chain value"
+                    );
+                    if let Some(info) = self.pool_map.get_mut(&v3_pool) {
+                        info.is_weth_token0 = is_weth_token0;
+                    }
+                    mismatches += 1;
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    warn!(
+                        v3_pool = %v3_pool,
+                        v2_pool = %info.v2_pool,
+                        "v2 pair doesn't hold the base token, dropping pool"
+                    );
+                    self.pool_map.remove(&v3_pool);
+                    mismatches += 1;
+                }
+                Err(err) => {
+                    warn!(v2_pool = %info.v2_pool, "Failed to fetch v2 pair tokens: {err:?}");
+                }
+            }
+        }
+        mismatches
+    }
+
+    /// Why the pool record for `v3_pool` doesn't match chain state, or `None`
+    /// if it does.
+    async fn pool_record_problem(
+        &self,
+        v3_pool: Address,
+        info: &V2PoolInfo,
+    ) -> Result<Option<String>> {
+        if self.provider.get_code_at(v3_pool).await?.is_empty() {
+            return Ok(Some("v3 pool is not a contract".to_string()));
+        }
+        if self.provider.get_code_at(info.v2_pool).await?.is_empty() {
+            return Ok(Some("v2 pool is not a contract".to_string()));
+        }
+        let pair = IUniswapV2Pair::new(info.v2_pool, self.provider.clone());
+        let reserves = pair.getReserves().call().await?;
+        if reserves.reserve0.is_zero() || reserves.reserve1.is_zero() {
+            return Ok(Some("v2 pool has no reserves".to_string()));
+        }
+        Ok(None)
+    }
+
+    /// Drop every pool record that doesn't match chain state, returning how
+    /// many were dropped. Records that can't be checked are kept. The
+    /// `weth_token0` flags are checked separately, by [Self::verify_pool_tokens].
+    async fn validate_pool_records(&mut self) -> usize {
+        let this = &*self;
+        let checked: Vec<_> = futures::stream::iter(this.pool_records())
+            .map(|(v3_pool, info)| async move {
+                let problem = this.pool_record_problem(v3_pool, &info).await;
+                (v3_pool, info, problem)
+            })
+            .buffer_unordered(POOL_CHECK_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut dropped = 0;
+        for (v3_pool, info, problem) in checked {
+            match problem {
+                Ok(None) => {}
+                Ok(Some(problem)) => {
+                    warn!(
+                        v3_pool = %v3_pool,
+                        v2_pool = %info.v2_pool,
+                        "dropping pool: {problem}"
+                    );
+                    self.pool_map.remove(&v3_pool);
+                    dropped += 1;
+                }
+                Err(err) => {
+                    warn!(v3_pool = %v3_pool, "Failed to validate pool: {err:?}");
+                }
+            }
+        }
+        dropped
+    }
+
+    /// Whether the pool filter currently permits backrunning `pool`.
+    fn permits_pool(&self, pool: &Address) -> bool {
+        self.pool_filter
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .permits(pool)
+    }
+
+    /// Record the bundles the relay accepted since the last event, returning
+    /// cancellations for those whose opportunity was already superseded.
+    fn record_submitted_bundles(&self) -> Vec<Action> {
+        let Some(receiver) = &self.submitted_bundles else {
+            return vec![];
+        };
+        let mut receiver = receiver.lock().unwrap_or_else(|e| e.into_inner());
+        let mut pending = self
+            .pending_bundles
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut cancellations = vec![];
+        while let Ok(submitted) = receiver.try_recv() {
+            let Some(BundleItem::Hash { hash: victim }) = submitted.bundle.bundle_body.first()
+            else {
+                continue;
+            };
+            if let Some(stale) = pending.record(*victim, submitted.bundle_hash) {
+                info!("Cancelling bundle {stale} for a superseded opportunity");
+                cancellations.push(Action::CancelBundle(stale));
+            }
+        }
+        cancellations
+    }
+
+    /// The raw transaction of the victim `tx_hash`, if the provider has it.
+    async fn fetch_victim_tx(&self, tx_hash: B256) -> Option<Bytes> {
+        match self.provider.get_raw_transaction_by_hash(tx_hash).await {
+            Ok(tx) => tx,
+            Err(err) => {
+                info!("Failed to fetch victim tx {tx_hash}: {err:?}");
+                None
+            }
+        }
+    }
+
+    /// Simulate the signed arb `tx` behind `victim_tx` in `block` with
+    /// `eth_callBundle`, returning the ETH the arb paid to the builder if both
+    /// succeed, and `None` if either reverts.
+    async fn simulate_arb(
+        &self,
+        victim_tx: Bytes,
+        tx: Bytes,
+        block: u64,
+    ) -> Result<Option<AlloyU256>> {
+        let Some((relay, auth_signer)) = &self.simulator else {
+            return Ok(Some(AlloyU256::ZERO));
+        };
+        let bundle = EthCallBundle {
+            txs: vec![victim_tx, tx],
+            block_number: block,
+            state_block_number: BlockNumberOrTag::Latest,
+            ..Default::default()
+        };
+        let response = relay
+            .call_bundle(bundle)
+            .with_auth(auth_signer.clone())
+            .await
+            .context("eth_callBundle failed")?
+            .context("eth_callBundle returned no response")?;
+        if response
+            .results
+            .iter()
+            .any(|result| result.revert.is_some() || result.error.is_some())
+        {
+            return Ok(None);
+        }
+        let [_, arb] = response.results.as_slice() else {
+            anyhow::bail!(
+                "eth_callBundle returned {} results for 2 txs",
+                response.results.len()
+            );
+        };
+        Ok(Some(arb.eth_sent_to_coinbase))
+    }
+
+    /// Simulate the arb `tx` of `size`, as [simulate_arb](Self::simulate_arb),
+    /// logging and returning `None` if it reverts or can't be simulated.
+    async fn check_arb(
+        &self,
+        victim_tx: &Bytes,
+        tx: &Bytes,
+        v3_address: Address,
+        size: AlloyU256,
+        block: u64,
+    ) -> Option<AlloyU256> {
+        match self
+            .simulate_arb(victim_tx.clone(), tx.clone(), block)
+            .await
+        {
+            Ok(Some(coinbase_payment)) => {
+                info!(pool = %v3_address, size = %size, coinbase_payment = %coinbase_payment, "simulated arb succeeded");
+                Some(coinbase_payment)
+            }
+            Ok(None) => {
+                info!(pool = %v3_address, size = %size, "simulated arb reverted, skipping");
+                None
+            }
+            Err(err) => {
+                info!("Failed to simulate arb, skipping: {err:?}");
+                None
+            }
+        }
+    }
+
+    /// Sign the arb tx for `size` and, if simulation is enabled and the
+    /// victim is known, check that it succeeds behind `victim_tx` in `block`,
+    /// re-bidding the builder payment from the simulated profit and checking
+    /// the re-signed tx again. Returns `None` if either fails, and otherwise
+    /// the tx along with its simulated net profit, as by
+    /// [ProfitEstimate::net], if known.
+    async fn prepare_arb_tx(
+        &self,
+        wallet: &W,
+        v3_address: Address,
+        size: AlloyU256,
+        params: ArbTxParams,
+        block: u64,
+        victim_tx: Option<&Bytes>,
+    ) -> Option<(Bytes, Option<f64>)> {
+        let bytes = match self.sign_arb_tx(wallet, v3_address, size, params).await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                info!("Failed to sign arb transaction: {err:?}");
+                return None;
+            }
+        };
+        let Some(victim_tx) = victim_tx.filter(|_| self.simulator.is_some()) else {
+            return Some((bytes, None));
+        };
+        let coinbase_payment = self
+            .check_arb(victim_tx, &bytes, v3_address, size, block)
+            .await?;
+
+        // Arbs that pay nothing to the builder leave the simulated profit
+        // unknown, so keep the bid as is.
+        let Some(gross) = simulated_gross_profit(coinbase_payment, params.payment_percentage)
+            .filter(|_| !coinbase_payment.is_zero())
+        else {
+            return Some((bytes, None));
+        };
+        let payment_percentage = self.payment_percentage(size, Some(f64::from(gross)));
+        let (bytes, coinbase_payment) = if payment_percentage == params.payment_percentage {
+            (bytes, coinbase_payment)
+        } else {
+            let params = ArbTxParams {
+                payment_percentage,
+                ..params
+            };
+            let bytes = match self.sign_arb_tx(wallet, v3_address, size, params).await {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    info!("Failed to re-sign arb transaction: {err:?}");
+                    return None;
+                }
+            };
+            // the new bid changes what the arb pays, so check it again
+            let coinbase_payment = self
+                .check_arb(victim_tx, &bytes, v3_address, size, block)
+                .await?;
+            (bytes, coinbase_payment)
+        };
+        let coinbase_payment = f64::from(coinbase_payment);
+        let simulated = ProfitEstimate {
+            gross: f64::from(gross),
+            gas_cost: (ARB_GAS_LIMIT as u128 * params.gas_price) as f64,
+            coinbase_payment,
+            refund: user_refund(coinbase_payment, self.user_refund_percent),
+        };
+        Some((bytes, Some(simulated.net())))
+    }
+
+    /// The backrun sizes to submit for an opportunity right now.
+    async fn opportunity_sizes(&self, base_token: Address) -> Vec<AlloyU256> {
+        let Some(percentages) = &self.balance_percentages else {
+            return self.sizes.clone();
+        };
+        match IWETH::new(base_token, self.provider.clone())
+            .balanceOf(*self.arb_contract.address())
+            .call()
+            .await
+        {
+            Ok(balance) => balance_sizes(balance, percentages),
+            Err(err) => {
+                info!("Failed to fetch arb contract balance, using absolute sizes: {err:?}");
+                self.sizes.clone()
+            }
+        }
+    }
+
+    /// Whether sizes need a profit estimate, which costs a v2 reserves lookup
+    /// per opportunity: to gate on profit, to convert the builder payment
+    /// floor, or to rank sizes when only some are submitted.
+    fn needs_profit_estimate(&self) -> bool {
+        !self.min_profit_wei.is_zero()
+            || !self.min_coinbase_payment_wei.is_zero()
+            || self.submit_best_only
+            || self.max_bundles_per_event.is_some()
+    }
+
     /// Generate a series of bundles of varying sizes to submit to the matchmaker.
+    /// Profit is estimated, when [needed](Self::needs_profit_estimate), at the
+    /// v3 pool's current price, read with [fetch_v3_state].
     pub async fn generate_bundles(&self, v3_address: Address, tx_hash: B256) -> Vec<MevSendBundle> {
+        self.generate_bundles_at_price(v3_address, tx_hash, None)
+            .await
+    }
+
+    /// Like [generate_bundles](Self::generate_bundles), but with the v3 pool
+    /// price after the victim's swap, used to estimate the profit of each size
+    /// when [needed](Self::needs_profit_estimate).
+    pub async fn generate_bundles_with_price(
+        &self,
+        v3_address: Address,
+        tx_hash: B256,
+        sqrt_price_x96: U160,
+    ) -> Vec<MevSendBundle> {
+        self.generate_bundles_at_price(v3_address, tx_hash, Some(sqrt_price_x96))
+            .await
+    }
+
+    async fn generate_bundles_at_price(
+        &self,
+        v3_address: Address,
+        tx_hash: B256,
+        sqrt_price_x96: Option<U160>,
+    ) -> Vec<MevSendBundle> {
         let mut bundles = Vec::new();
-        let v2_info = self.pool_map.get(&v3_address).unwrap();
-
-        // The sizes of the backruns we want to submit.
-        // TODO: Run some analysis to figure out likely sizes.
-        let sizes = vec![
-            AlloyU256::from(100_000_u128),
-            AlloyU256::from(1_000_000_u128),
-            AlloyU256::from(10_000_000_u128),
-            AlloyU256::from(100_000_000_u128),
-            AlloyU256::from(1_000_000_000_u128),
-            AlloyU256::from(10_000_000_000_u128),
-            AlloyU256::from(100_000_000_000_u128),
-            AlloyU256::from(1_000_000_000_000_u128),
-            AlloyU256::from(10_000_000_000_000_u128),
-            AlloyU256::from(100_000_000_000_000_u128),
-            AlloyU256::from(1_000_000_000_000_000_u128),
-            AlloyU256::from(10_000_000_000_000_000_u128),
-            AlloyU256::from(100_000_000_000_000_000_u128),
-            AlloyU256::from(1_000_000_000_000_000_000_u128),
-        ];
+        let v2_info = self.pool_map.get(&v3_address).unwrap().clone();
 
         // Set parameters for the backruns.
-        let payment_percentage = AlloyU256::ZERO;
-        let bid_gas_price = match self.provider.get_gas_price().await {
+        let bid_gas_price = match retry(PROVIDER_ATTEMPTS, PROVIDER_RETRY_DELAY, || async {
+            self.provider.get_gas_price().await
+        })
+        .await
+        {
             Ok(price) => price,
             Err(err) => {
                 info!("Failed to fetch gas price: {err:?}");
                 return bundles;
             }
         };
-        let block_num = match self.provider.get_block_number().await {
+        if bid_gas_price > self.max_gas_price_wei {
+            warn!(
+                gas_price = bid_gas_price,
+                max_gas_price = self.max_gas_price_wei,
+                "Skipping v3 pool {:?}: gas price above max, likely bad RPC data",
+                v3_address
+            );
+            return bundles;
+        }
+        let block_num = match retry(PROVIDER_ATTEMPTS, PROVIDER_RETRY_DELAY, || async {
+            self.provider.get_block_number().await
+        })
+        .await
+        {
             Ok(number) => number,
             Err(err) => {
                 info!("Failed to fetch block number: {err:?}");
                 return bundles;
             }
         };
-        let chain_id = match self.provider.get_chain_id().await {
-            Ok(id) => id,
-            Err(err) => {
-                info!("Failed to fetch chain id: {err:?}");
-                return bundles;
+        let last_submission = self
+            .last_submission
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&v3_address)
+            .copied();
+        if let Some((submitted_at, submitted_block)) = last_submission
+            && self
+                .cooldown
+                .is_active(submitted_at, submitted_block, block_num)
+        {
+            info!("Skipping v3 pool {:?} on cooldown", v3_address);
+            return bundles;
+        }
+        if let Some(max_base_fee) = self.max_base_fee {
+            match self
+                .provider
+                .get_block_by_number(BlockNumberOrTag::Latest)
+                .await
+            {
+                Ok(Some(block)) => {
+                    if let Some(base_fee) = block.header.base_fee_per_gas
+                        && base_fee > max_base_fee
+                    {
+                        info!(
+                            "Skipping v3 pool {:?}: base fee {} exceeds max {}",
+                            v3_address, base_fee, max_base_fee
+                        );
+                        return bundles;
+                    }
+                }
+                Ok(None) => info!("Latest block not found, skipping base fee check"),
+                Err(err) => info!("Failed to fetch latest block for base fee check: {err:?}"),
             }
+        }
+        let chain_id = match self.chain_id.get() {
+            Some(&id) => id,
+            None => match retry(PROVIDER_ATTEMPTS, PROVIDER_RETRY_DELAY, || async {
+                self.provider.get_chain_id().await
+            })
+            .await
+            {
+                Ok(id) => *self.chain_id.get_or_init(|| id),
+                Err(err) => {
+                    info!("Failed to fetch chain id: {err:?}");
+                    return bundles;
+                }
+            },
         };
-        let sender = self.wallet.default_signer_address();
-        let nonce = match self.provider.get_transaction_count(sender).await {
+        let wallet = self.wallets
+            [self.next_wallet.fetch_add(1, Ordering::Relaxed) % self.wallets.len()]
+        .clone();
+        let sender = wallet.default_signer_address();
+        let nonce = match retry(PROVIDER_ATTEMPTS, PROVIDER_RETRY_DELAY, || async {
+            self.provider.get_transaction_count(sender).await
+        })
+        .await
+        {
             Ok(value) => value,
             Err(err) => {
                 info!("Failed to fetch signer nonce: {err:?}");
@@ -182,34 +1722,168 @@ where
             }
         };
 
+        // Fetch v2 reserves for profit estimates, if they're needed, and the
+        // v3 price if the swap didn't give it. Without them we can't gate on
+        // profit, but still submit if no profit threshold is set. Profit is
+        // only estimated for wrapped native arbs, as the bid and threshold are
+        // denominated in it.
+        let base_token = self.base_token(&v2_info);
+        let sqrt_price_x96 = match (base_token, sqrt_price_x96) {
+            _ if !self.needs_profit_estimate() => None,
+            (Some(_), _) => None,
+            (None, Some(sqrt_price_x96)) => Some(sqrt_price_x96),
+            (None, None) => match fetch_v3_state(self.provider.clone(), v3_address).await {
+                Ok(state) => Some(state.sqrt_price_x96),
+                Err(err) => {
+                    info!("Failed to fetch v3 pool state: {err:?}");
+                    None
+                }
+            },
+        };
+        let reserves = match (base_token, sqrt_price_x96) {
+            (Some(_), _) | (_, None) => None,
+            (None, Some(_)) => match IUniswapV2Pair::new(v2_info.v2_pool, self.provider.clone())
+                .getReserves()
+                .call()
+                .await
+            {
+                Ok(reserves) => Some((
+                    AlloyU256::from(reserves.reserve0),
+                    AlloyU256::from(reserves.reserve1),
+                )),
+                Err(err) => {
+                    info!("Failed to fetch v2 reserves: {err:?}");
+                    None
+                }
+            },
+        };
+        let mut candidates = Vec::new();
+
+        // The threshold is in wei of the wrapped native token, which base token
+        // arbs aren't estimated in.
+        let min_profit_wei = match base_token {
+            Some(base_token) if !self.min_profit_wei.is_zero() => {
+                info!(
+                    pool = %v3_address,
+                    base_token = %base_token,
+                    "Not applying the profit threshold to a base token arb"
+                );
+                AlloyU256::ZERO
+            }
+            _ => self.min_profit_wei,
+        };
+        let sizes = self
+            .opportunity_sizes(base_token.unwrap_or(self.wrapped_native))
+            .await;
         for size in sizes {
-            // Construct arb tx based on whether the v2 pool has weth as token0.
-            let mut tx = if v2_info.is_weth_token0 {
-                self.arb_contract
-                    .executeArb__WETH_token0(v2_info.v2_pool, v3_address, size, payment_percentage)
-                    .into_transaction_request()
-            } else {
-                self.arb_contract
-                    .executeArb__WETH_token1(v2_info.v2_pool, v3_address, size, payment_percentage)
-                    .into_transaction_request()
-            };
-            tx.set_from(sender);
-            tx.set_nonce(nonce);
-            tx.set_chain_id(chain_id);
-            tx.set_gas_limit(400_000);
-            tx.set_gas_price(bid_gas_price);
-            tx.set_value(AlloyU256::ZERO);
+            let gross = reserves
+                .zip(sqrt_price_x96)
+                .and_then(|(reserves, sqrt_price_x96)| {
+                    estimate_gross_profit(size, sqrt_price_x96, reserves, v2_info.is_weth_token0)
+                });
+            let payment_percentage = self.payment_percentage(size, gross);
+            let estimate = gross.map(|gross| {
+                let coinbase_payment = gross.max(0.0) * f64::from(payment_percentage) / 100.0;
+                ProfitEstimate {
+                    gross,
+                    gas_cost: (ARB_GAS_LIMIT as u128 * bid_gas_price) as f64,
+                    coinbase_payment,
+                    refund: user_refund(coinbase_payment, self.user_refund_percent),
+                }
+            });
+            if let Some(estimate) = estimate {
+                info!(
+                    pool = %v3_address,
+                    size = %size,
+                    gross_profit = estimate.gross as i128,
+                    gas_cost = estimate.gas_cost as i128,
+                    coinbase_payment = estimate.coinbase_payment as i128,
+                    user_refund = estimate.refund as i128,
+                    net_profit = estimate.net() as i128,
+                    "estimated arb profit"
+                );
+            }
+
+            // Skip sizes that don't clear the profit threshold, if one is set.
+            let net_profit = estimate.map(|estimate| estimate.net());
+            if !min_profit_wei.is_zero()
+                && net_profit.is_none_or(|profit| profit < f64::from(min_profit_wei))
+            {
+                continue;
+            }
 
-            info!("generated arb tx: {:?}", tx);
+            let params = ArbTxParams {
+                nonce,
+                chain_id,
+                gas_price: bid_gas_price,
+                payment_percentage,
+            };
+            candidates.push((size, params, net_profit));
+        }
+        if let Some(sender) = self.opportunity_sender.as_ref().filter(|s| !s.is_closed()) {
+            let opportunity = OpportunityDetected {
+                pool: v3_address,
+                tx_hash,
+                sizes: candidates.iter().map(|&(size, ..)| size).collect(),
+                block: block_num,
+                timestamp: SystemTime::now(),
+            };
+            // the receiver may go away at any time, dropping the record
+            let _ = sender.send(opportunity);
+        }
 
-            let envelope = match tx.clone().build(&self.wallet).await {
-                Ok(env) => env,
-                Err(err) => {
-                    info!("Failed to sign arb transaction: {err:?}");
-                    continue;
+        // Every size shares the parameters fetched above, so sign and simulate
+        // them concurrently, at most `sign_concurrency` at a time.
+        let victim_tx = match self.simulator.is_some() && !candidates.is_empty() {
+            true => self.fetch_victim_tx(tx_hash).await,
+            false => None,
+        };
+        let (min_block_offset, max_block_offset) = self.block_target.offsets();
+        let block = block_num + min_block_offset;
+        let this = self;
+        let mut signed = Vec::with_capacity(candidates.len());
+        for chunk in candidates.chunks(this.sign_concurrency) {
+            let results = join_all(chunk.iter().map(|&(size, params, net_profit)| {
+                let (wallet, victim_tx) = (&wallet, victim_tx.as_ref());
+                async move {
+                    let (bytes, simulated_profit) = this
+                        .prepare_arb_tx(wallet, v3_address, size, params, block, victim_tx)
+                        .await?;
+                    // hold simulated profits to the same threshold as estimates
+                    if let Some(profit) = simulated_profit
+                        && !min_profit_wei.is_zero()
+                        && profit < f64::from(min_profit_wei)
+                    {
+                        info!(pool = %v3_address, size = %size, "simulated profit below threshold, skipping");
+                        return None;
+                    }
+                    // simulated profit is net like the estimate, and more accurate
+                    Some((size, simulated_profit.or(net_profit), bytes))
                 }
-            };
-            let bytes = Bytes::from(envelope.encoded_2718());
+            }))
+            .await;
+            signed.extend(results.into_iter().flatten());
+        }
+        let max_bundles = match self.submit_best_only {
+            true => Some(1),
+            false => self.max_bundles_per_event,
+        };
+        if let Some(max_bundles) = max_bundles.filter(|&max| max < signed.len()) {
+            let profit = |net_profit: Option<f64>| net_profit.unwrap_or(f64::NEG_INFINITY);
+            signed.sort_by(|a, b| profit(b.1).total_cmp(&profit(a.1)));
+            signed.truncate(max_bundles);
+        }
+        // the best of the sizes actually submitted, none if nothing is
+        let best_profit = signed.iter().filter_map(|signed| signed.1).reduce(f64::max);
+
+        let builders = match &self.builder_weights {
+            Some(weights) => weights
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .select(&self.builders),
+            None => self.builders.clone(),
+        };
+        for (size, net_profit, bytes) in signed {
             let txs = vec![
                 BundleItem::Hash { hash: tx_hash },
                 BundleItem::Tx {
@@ -220,27 +1894,320 @@ where
             let bundle = MevSendBundle {
                 protocol_version: ProtocolVersion::V0_1,
                 inclusion: Inclusion {
-                    block: block_num + 1,
-                    // set a large validity window to ensure builder gets a chance to include bundle.
-                    max_block: Some(block_num + 30),
+                    block,
+                    // keep the window tight so stale arbs don't land after the opportunity is gone.
+                    max_block: Some(block_num + max_block_offset),
                 },
                 bundle_body: txs,
-                validity: None,
+                validity: refund_split_validity(&self.refund_split).or_else(|| {
+                    refund_validity(self.refund_percent, self.refund_recipient, sender)
+                }),
                 privacy: Some(Privacy {
-                    hints: None,
-                    builders: Some(vec![
-                        "flashbots".into(),
-                        "Titan".into(),
-                        "rsync".into(),
-                        "beaverbuild.org".into(),
-                        "builder0x69".into(),
-                        "Quasar".into(),
-                    ]),
+                    hints: self.privacy_hints,
+                    builders: Some(builders.clone()),
                 }),
             };
             info!("submitting bundle: {:?}", bundle);
+            if let Some(sender) = self.submission_sender.as_ref().filter(|s| !s.is_closed()) {
+                let submission = BundleSubmission {
+                    pool: v3_address,
+                    tx_hash,
+                    size,
+                    block: bundle.inclusion.block,
+                    max_block: bundle.inclusion.max_block.unwrap_or(bundle.inclusion.block),
+                    expected_profit: net_profit,
+                    timestamp: SystemTime::now(),
+                };
+                let _ = sender.send(submission);
+            }
             bundles.push(bundle);
         }
+
+        if !bundles.is_empty() {
+            self.last_submission
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(v3_address, (Instant::now(), block_num));
+        }
+
+        // At most one size lands, and only if it's profitable.
+        if let Some(best_profit) = best_profit {
+            self.pnl
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record(best_profit.max(0.0));
+        }
         bundles
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::network::EthereumWallet;
+    use alloy::primitives::{I256, LogData, aliases::I24};
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::sol_types::SolValue;
+    use artemis_core::test_util::MockProvider;
+
+    fn swap_log(amount0: i64, amount1: i64) -> Log {
+        let swap = IUniswapV3Pool::Swap {
+            sender: Address::ZERO,
+            recipient: Address::ZERO,
+            amount0: I256::try_from(amount0).unwrap(),
+            amount1: I256::try_from(amount1).unwrap(),
+            sqrtPriceX96: U160::ZERO,
+            liquidity: 0,
+            tick: I24::ZERO,
+        };
+        Log {
+            address: Address::ZERO,
+            data: swap.encode_log_data(),
+        }
+    }
+
+    #[test]
+    fn reads_pool_records() {
+        let csv = "token_address,v3_pool,v2_pool,weth_token0\n\
+            0x16484d73ac08d2355f466d448d2b79d2039f6ebb,0x640784681a4fdf860f9f266bd4c063fc2efc216e,0xafd88ed9906ef1c7d46189314452aa4b3d665b8e,false\n\
+            0xe1b583dc66e0a24fd9af2dc665f6f5e48978e106,0xa497e3d00adedc8886f644ee6c03c3f2ee67d0e3,0x3f888099223bf16fa69bb35a046be5bd0425411f,true\n";
+        let pools = read_pools(csv::Reader::from_reader(csv.as_bytes()), 2).unwrap();
+        assert_eq!(pools.len(), 2);
+        let pool = &pools[&address!("0xa497e3d00adedc8886f644ee6c03c3f2ee67d0e3")];
+        assert_eq!(
+            pool.v2_pool,
+            address!("0x3f888099223bf16fa69bb35a046be5bd0425411f")
+        );
+        assert!(pool.is_weth_token0);
+    }
+
+    #[test]
+    fn reads_optional_base_token() {
+        let usdc = address!("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        let csv = format!(
+            "token_address,v3_pool,v2_pool,weth_token0,base_token\n\
+            {a},{a},{a},false,\n\
+            {b},{b},{b},true,{usdc}\n",
+            a = Address::repeat_byte(1),
+            b = Address::repeat_byte(2),
+        );
+        let pools = read_pools(csv::Reader::from_reader(csv.as_bytes()), 2).unwrap();
+        assert_eq!(pools[&Address::repeat_byte(1)].base_token, None);
+        assert_eq!(pools[&Address::repeat_byte(2)].base_token, Some(usdc));
+    }
+
+    #[test]
+    fn simulated_gross_profit_from_coinbase_payment() {
+        let percent = |p: u64| AlloyU256::from(p);
+        assert_eq!(simulated_gross_profit(AlloyU256::ZERO, percent(0)), None);
+        assert_eq!(
+            simulated_gross_profit(AlloyU256::from(25), percent(25)),
+            Some(AlloyU256::from(100))
+        );
+    }
+
+    #[tokio::test]
+    async fn pool_verification_corrects_weth_token0() {
+        let provider = MockProvider::new();
+        let wallet = EthereumWallet::from(PrivateKeySigner::random());
+        let mut strategy = MevShareUniArb::new(Arc::new(provider.clone()), wallet, Address::ZERO);
+        let v3_pool = Address::repeat_byte(1);
+        strategy.pool_map = HashMap::from([(
+            v3_pool,
+            V2PoolInfo {
+                v2_pool: Address::repeat_byte(2),
+                is_weth_token0: true,
+                base_token: None,
+            },
+        )]);
+
+        // token0 isn't weth, but token1 is.
+        let token = |address: Address| Bytes::from(address.abi_encode());
+        provider
+            .asserter()
+            .push_success(&token(Address::repeat_byte(3)));
+        provider.asserter().push_success(&token(WETH));
+        assert_eq!(strategy.verify_pool_tokens().await, 1);
+        assert!(!strategy.pool_map[&v3_pool].is_weth_token0);
+    }
+
+    #[test]
+    fn pool_filter_blocks_and_allows() {
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut filter = PoolFilter::default();
+        assert!(filter.permits(&a) && filter.permits(&b));
+
+        filter.block(a);
+        assert!(!filter.permits(&a) && filter.permits(&b));
+
+        filter.unblock(&a);
+        filter.set_allowlist(Some(HashSet::from([a])));
+        assert!(filter.permits(&a) && !filter.permits(&b));
+
+        // The blocklist wins over the allowlist.
+        filter.block(a);
+        assert!(!filter.permits(&a));
+    }
+
+    #[test]
+    fn balance_sizes_are_clamped() {
+        let balance = AlloyU256::from(1_000_000u64);
+        assert_eq!(
+            balance_sizes(balance, &[0.0, 1.0, 50.0, 150.0]),
+            vec![
+                AlloyU256::from(10_000u64),
+                AlloyU256::from(500_000u64),
+                balance
+            ]
+        );
+    }
+
+    #[test]
+    fn next_block_only_targets_a_single_block() {
+        assert_eq!(BlockTarget::NextBlockOnly.offsets(), (1, 1));
+        assert_eq!(BlockTarget::Window { min: 2, max: 1 }.offsets(), (2, 2));
+    }
+
+    #[test]
+    fn builder_weights_drop_builders_without_recent_wins() {
+        let builders: Vec<String> = ["flashbots", "Titan", "rsync"].map(String::from).to_vec();
+        let mut weights = BuilderWeights::new(4, 0.3)
+            .with_builder("0xaa", "flashbots")
+            .with_builder("0xbb", "Titan")
+            .with_builder("0xcc", "rsync");
+        assert_eq!(weights.select(&builders), builders);
+
+        let win = |pubkey: &str| BuilderBlockStats {
+            block_number: 1,
+            block_hash: B256::ZERO,
+            builder_pubkey: pubkey.to_string(),
+            gas_used: 0,
+            num_tx: 0,
+            value: AlloyU256::ZERO,
+        };
+        for pubkey in ["0xaa", "0xbb", "0xbb", "0xcc", "0xbb"] {
+            weights.record(&win(pubkey));
+        }
+        // The first win has left the window, and rsync won too few blocks.
+        assert_eq!(weights.select(&builders), vec!["Titan".to_string()]);
+        assert_eq!(weights.win_rate("Titan"), 0.75);
+    }
+
+    #[test]
+    fn finds_swap_after_other_logs() {
+        let pool = Address::repeat_byte(1);
+        let transfer = Log {
+            address: pool,
+            data: LogData::new_unchecked(vec![B256::repeat_byte(2)], Bytes::new()),
+        };
+        let unknown_swap = swap_log(1, -1);
+        let mut known_swap = swap_log(-1, 1);
+        known_swap.address = pool;
+
+        let logs = [unknown_swap, transfer, known_swap];
+        let (address, _, zero_for_one) = find_swap(&logs, |address| *address == pool).unwrap();
+        assert_eq!(address, pool);
+        assert!(!zero_for_one);
+        assert!(find_swap(&logs[..2], |address| *address == pool).is_none());
+    }
+
+    #[test]
+    fn refund_recipient_defaults_to_signer() {
+        let (signer, recipient) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        assert!(refund_validity(None, Some(recipient), signer).is_none());
+
+        let config =
+            |validity: Option<Validity>| validity.unwrap().refund_config.unwrap()[0].address;
+        assert_eq!(config(refund_validity(Some(50), None, signer)), signer);
+        assert_eq!(
+            config(refund_validity(Some(50), Some(recipient), signer)),
+            recipient
+        );
+    }
+
+    #[test]
+    fn refund_split_fills_refund_config() {
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        assert!(refund_split_validity(&[]).is_none());
+
+        let config = refund_split_validity(&[(a, 60), (b, 40)])
+            .unwrap()
+            .refund_config
+            .unwrap();
+        assert_eq!(config.len(), 2);
+        assert_eq!((config[1].address, config[1].percent), (b, 40));
+        assert!(check_refund_split(&[(a, 60), (b, 41)]).is_err());
+    }
+
+    #[test]
+    fn pending_bundles_cancel_superseded_opportunities() {
+        let pool = Address::repeat_byte(1);
+        let (first, second) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        let (a, b, c) = (
+            B256::repeat_byte(10),
+            B256::repeat_byte(11),
+            B256::repeat_byte(12),
+        );
+        let mut pending = PendingBundles::default();
+        assert!(pending.supersede(pool, first, 11, 13).is_empty());
+        assert_eq!(pending.record(first, a), None);
+        assert!(pending.supersede(pool, first, 11, 13).is_empty());
+        assert_eq!(pending.record(first, b), None);
+
+        assert_eq!(pending.supersede(pool, second, 12, 14), vec![a, b]);
+        // a late hash for the superseded opportunity is handed straight back
+        assert_eq!(pending.record(first, c), Some(c));
+        assert_eq!(pending.record(second, c), None);
+        // bundles past their window are left alone
+        assert!(pending.supersede(pool, first, 15, 17).is_empty());
+    }
+
+    #[test]
+    fn cooldown_expires() {
+        let now = Instant::now();
+        assert!(!Cooldown::None.is_active(now, 10, 10));
+        assert!(Cooldown::UntilNextBlock.is_active(now, 10, 10));
+        assert!(!Cooldown::UntilNextBlock.is_active(now, 10, 11));
+        assert!(Cooldown::For(Duration::from_secs(60)).is_active(now, 10, 11));
+        assert!(!Cooldown::For(Duration::ZERO).is_active(now, 10, 10));
+    }
+
+    #[test]
+    fn decodes_swap_direction() {
+        let direction = |log: &Log| decode_swap(log).and_then(|swap| swap_direction(&swap));
+        assert_eq!(direction(&swap_log(100, -50)), Some(true));
+        assert_eq!(direction(&swap_log(-100, 50)), Some(false));
+        assert_eq!(direction(&Log::default()), None);
+
+        let weth_token0 = V2PoolInfo {
+            v2_pool: Address::ZERO,
+            is_weth_token0: true,
+            base_token: None,
+        };
+        assert!(weth_token0.can_backrun(false));
+        assert!(!weth_token0.can_backrun(true));
+    }
+
+    #[test]
+    fn default_sizes_are_powers_of_ten() {
+        let sizes = geometric_sizes(DEFAULT_MIN_SIZE, DEFAULT_MAX_SIZE, DEFAULT_SIZE_STEPS);
+        let expected: Vec<AlloyU256> = (5..=18)
+            .map(|exp| AlloyU256::from(10u128.pow(exp)))
+            .collect();
+        assert_eq!(sizes, expected);
+    }
+
+    #[test]
+    fn size_range_edge_cases() {
+        assert!(geometric_sizes(1, 100, 0).is_empty());
+        assert_eq!(geometric_sizes(42, 100, 1), vec![AlloyU256::from(42u128)]);
+        assert_eq!(
+            geometric_sizes(1, 100, 3),
+            vec![
+                AlloyU256::from(1u128),
+                AlloyU256::from(10u128),
+                AlloyU256::from(100u128)
+            ]
+        );
+    }
+}
@@ -1,17 +1,58 @@
-use alloy::primitives::Address;
-use alloy::rpc::types::mev::MevSendBundle;
+use std::time::SystemTime;
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::rpc::types::mev::{EthSendBundle, MevSendBundle};
 use artemis_core::mevshare;
 
 /// Core Event enum for the current strategy.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum Event {
     MEVShareEvent(mevshare::Event),
 }
 
 /// Core Action enum for the current strategy.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum Action {
     SubmitBundle(MevSendBundle),
+    /// A plain Flashbots bundle for a builder, without MEV-Share refunds.
+    SubmitEthBundle(EthSendBundle),
+    /// Cancel a submitted bundle, by the bundle hash the relay returned for
+    /// it. Emitted once a newer opportunity on the same pool is submitted, if
+    /// bundle cancellation is enabled.
+    CancelBundle(B256),
+}
+
+/// Metadata about a submitted bundle, for offline analysis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleSubmission {
+    /// The uni v3 pool the victim swapped on.
+    pub pool: Address,
+    /// Hash of the victim transaction being backrun.
+    pub tx_hash: B256,
+    /// Size of the backrun, in wei.
+    pub size: U256,
+    /// First block the bundle is valid for.
+    pub block: u64,
+    /// Last block the bundle is valid for.
+    pub max_block: u64,
+    /// Estimated net profit in wei, if reserves were available.
+    pub expected_profit: Option<f64>,
+    pub timestamp: SystemTime,
+}
+
+/// An arb opportunity the strategy sized, recorded whether or not any size
+/// cleared the profit threshold, to compare detections against submissions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpportunityDetected {
+    /// The uni v3 pool the victim swapped on.
+    pub pool: Address,
+    /// Hash of the victim transaction.
+    pub tx_hash: B256,
+    /// Sizes that cleared the profit threshold, in wei. Empty if none did.
+    pub sizes: Vec<U256>,
+    /// Block the opportunity was seen at.
+    pub block: u64,
+    pub timestamp: SystemTime,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -26,5 +67,9 @@ pub struct V2V3PoolRecord {
     pub token_address: Address,
     pub v3_pool: Address,
     pub v2_pool: Address,
+    /// Whether the base token is token0 of the v2 pool.
     pub weth_token0: bool,
+    /// Token the arb starts and ends in, if not the wrapped native token.
+    #[serde(default)]
+    pub base_token: Option<Address>,
 }
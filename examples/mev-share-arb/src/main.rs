@@ -1,26 +1,31 @@
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use alloy::{
-    network::EthereumWallet,
-    primitives::Address,
-    providers::{ProviderBuilder, WsConnect},
-    signers::local::PrivateKeySigner,
+    network::EthereumWallet, primitives::Address, providers::ProviderBuilder,
+    rpc::types::mev::MevSendBundle, signers::local::PrivateKeySigner, transports::Authorization,
 };
 use anyhow::{Context, Result};
 use artemis_core::types::{MEV_RELAY, MEV_SHARE};
 use artemis_core::{
     collectors::mevshare_collector::MevShareCollector,
     engine::Engine,
-    executors::mev_share_executor::MevshareExecutor,
-    types::{CollectorMap, ExecutorMap},
+    executors::{
+        flashbots_executor::FlashbotsExecutor,
+        mev_share_executor::{CancelBundle, MevshareExecutor},
+    },
+    logging::LogConfig,
+    types::{CollectorMap, Executor, ExecutorMap},
+    util::connect_ws_with_auth,
 };
 use clap::Parser;
 use mev_share_uni_arb::{
     strategy::MevShareUniArb,
     types::{Action, Event},
 };
-use tracing::{Level, info};
-use tracing_subscriber::{filter, prelude::*};
+use tokio::sync::{mpsc, watch};
+use tracing::{info, level_filters::LevelFilter};
 
 /// CLI Options.
 #[derive(Parser, Debug)]
@@ -28,6 +33,9 @@ pub struct Args {
     /// Ethereum node WS endpoint.
     #[arg(long)]
     pub wss: String,
+    /// Optional `Authorization` header for the WS endpoint, e.g. "Bearer <token>".
+    #[arg(long)]
+    pub wss_auth: Option<String>,
     /// Private key for sending txs.
     #[arg(long)]
     pub private_key: String,
@@ -37,29 +45,34 @@ pub struct Args {
     /// Address of the arb contract.
     #[arg(long)]
     pub arb_contract_address: Address,
+    /// Flashbots relay URL bundles are submitted to.
+    #[arg(long, default_value = MEV_RELAY)]
+    pub relay_url: String,
+    /// MEV-Share SSE endpoint events are streamed from.
+    #[arg(long, default_value = MEV_SHARE)]
+    pub mev_share_url: String,
+    /// Also submit each arb as a plain Flashbots bundle to the relay, when the
+    /// victim is in the public mempool.
+    #[arg(long)]
+    pub eth_bundles: bool,
+    /// Pool csv to load instead of the one bundled with the strategy.
+    #[arg(long)]
+    pub pools: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Set up tracing and parse args.
-    let filter = filter::Targets::new()
-        .with_target("mev_share_uni_arb", Level::INFO)
-        .with_target("artemis_core", Level::INFO);
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer())
-        .with(filter)
+    LogConfig::new(LevelFilter::OFF)
+        .with_target("mev_share_uni_arb", LevelFilter::INFO)
+        .with_target("artemis_core", LevelFilter::INFO)
         .init();
 
     let args = Args::parse();
 
     // Set up provider and signers.
-    let ws = WsConnect::new(args.wss.clone());
-    let provider = Arc::new(
-        ProviderBuilder::new()
-            .connect_ws(ws)
-            .await
-            .context("failed to connect websocket provider")?,
-    );
+    let auth = args.wss_auth.clone().map(Authorization::raw);
+    let provider = Arc::new(connect_ws_with_auth(args.wss.clone(), auth).await?);
 
     let wallet_signer: PrivateKeySigner = args
         .private_key
@@ -68,32 +81,75 @@ async fn main() -> Result<()> {
     let wallet = EthereumWallet::from(wallet_signer);
 
     let mev_provider = Arc::new(
-        ProviderBuilder::new().connect_http(MEV_RELAY.parse().expect("failed to parse relay url")),
+        ProviderBuilder::new().connect_http(
+            args.relay_url
+                .parse()
+                .context("failed to parse relay url")?,
+        ),
     );
     let fb_signer: PrivateKeySigner = args
         .flashbots_signer
         .parse()
         .context("failed to parse flashbots signer key")?;
 
-    // Set up engine.
-    let mut engine: Engine<Event, Action> = Engine::default();
+    // Set up engine, stopping cleanly on ctrl-c or SIGTERM: pending events are
+    // processed and bundles already produced are submitted before exiting.
+    let (stop, shutdown) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        info!("shutting down, draining in-flight bundles");
+        stop.send_replace(true);
+    });
+    let mut engine: Engine<Event, Action> = Engine::default()
+        .with_shutdown(shutdown)
+        .with_drain_on_shutdown(Duration::from_secs(5));
 
     // Set up collector.
-    let mevshare_collector = Box::new(MevShareCollector::new());
+    let mevshare_collector = Box::new(MevShareCollector::with_url(args.mev_share_url.clone()));
     let mevshare_collector = CollectorMap::new(mevshare_collector, Event::MEVShareEvent);
     engine.add_collector(Box::new(mevshare_collector));
 
-    // Set up strategy.
-    let strategy = MevShareUniArb::new(provider.clone(), wallet.clone(), args.arb_contract_address);
+    // Set up strategy, cancelling bundles superseded by a newer opportunity on
+    // the same pool, with the bundle hashes the MEV-Share executor reports.
+    let (submitted_sender, submitted_bundles) = mpsc::unbounded_channel();
+    let strategy = MevShareUniArb::new(provider.clone(), wallet.clone(), args.arb_contract_address)
+        .with_eth_bundles(args.eth_bundles)
+        .with_bundle_cancellation(submitted_bundles);
+    let strategy = match args.pools {
+        Some(path) => strategy.with_pools_path(path),
+        None => strategy,
+    };
     engine.add_strategy(Box::new(strategy));
 
-    // Set up executor.
-    let mev_share_executor = Box::new(MevshareExecutor::new(mev_provider, fb_signer));
+    // Set up executors.
+    let flashbots_executor = Box::new(FlashbotsExecutor::new(
+        mev_provider.clone(),
+        fb_signer.clone(),
+    ));
+    let flashbots_executor = ExecutorMap::new(flashbots_executor, |action| match action {
+        Action::SubmitEthBundle(bundle) => Some(bundle),
+        _ => None,
+    });
+    engine.add_executor(Box::new(flashbots_executor));
+
+    let mev_share_executor: Box<dyn Executor<MevSendBundle>> = Box::new(
+        MevshareExecutor::new(mev_provider.clone(), fb_signer.clone())
+            .with_submitted_sender(submitted_sender),
+    );
     let mev_share_executor = ExecutorMap::new(mev_share_executor, |action| match action {
         Action::SubmitBundle(bundle) => Some(bundle),
+        _ => None,
     });
     engine.add_executor(Box::new(mev_share_executor));
 
+    let cancel_executor: Box<dyn Executor<CancelBundle>> =
+        Box::new(MevshareExecutor::new(mev_provider, fb_signer));
+    let cancel_executor = ExecutorMap::new(cancel_executor, |action| match action {
+        Action::CancelBundle(bundle_hash) => Some(CancelBundle { bundle_hash }),
+        _ => None,
+    });
+    engine.add_executor(Box::new(cancel_executor));
+
     // Start engine.
     if let Ok(mut set) = engine.run().await {
         while let Some(res) = set.join_next().await {
@@ -103,3 +159,32 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Resolve on ctrl-c, or on SIGTERM on unix.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("failed to listen for ctrl-c: {e}");
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("failed to listen for SIGTERM: {e}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}